@@ -2,17 +2,42 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
-use serde::Serialize;
+use futures_util::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use webkit::glib::{self, DateTime, MainContext, SourceId};
+use zbus::{MatchRule, MessageStream, message};
 
-use std::sync::OnceLock;
+use std::{
+    cell::Cell,
+    ffi::CString,
+    path::{Path, PathBuf},
+    pin::pin,
+    rc::Rc,
+    sync::{OnceLock, RwLock},
+    time::Duration,
+};
 
-use super::dbus::AccountsService;
+use super::{
+    Layout, Session, SessionManager,
+    avatar::Avatar,
+    dbus::{
+        AccountsProxy, AccountsService, HomeEntry, HomedManager, LoginHistory, UserProxyBlocking,
+        system_conn,
+    },
+    state::GreeterState,
+};
+use crate::config::Config;
 
-#[derive(Debug, Serialize)]
+/// Time to wait for more signals of AccountsService or systemd-homed before reloading the users
+const USERS_RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize)]
 pub struct User {
     home_directory: Option<String>,
-    #[serde(rename(serialize = "image"))]
+    #[serde(skip_serializing)]
     icon_file: Option<String>,
+    /// URI of the resolved avatar
+    image: Option<String>,
     language: Option<String>,
     #[serde(rename(serialize = "display_name"))]
     real_name: Option<String>,
@@ -21,6 +46,122 @@ pub struct User {
     uid: Option<u32>,
     #[serde(rename(serialize = "username"))]
     user_name: Option<String>,
+    #[serde(flatten)]
+    status: AccountStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    #[default]
+    Standard,
+    Administrator,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordMode {
+    #[default]
+    Regular,
+    /// The password has to be chosen at next login
+    SetAtLogin,
+    /// No password is needed to log in
+    None,
+}
+
+/// A finished or ongoing login, times are seconds since the epoch
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LoginRecord {
+    login_time: i64,
+    /// 0 if the user is still logged in
+    logout_time: i64,
+}
+
+impl From<LoginHistory> for LoginRecord {
+    fn from(value: LoginHistory) -> Self {
+        Self {
+            login_time: value.login_time,
+            logout_time: value.logout_time,
+        }
+    }
+}
+
+/// Account state of AccountsService, users only known by pwd keep the defaults
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AccountStatus {
+    locked: bool,
+    account_type: AccountType,
+    password_hint: Option<String>,
+    password_mode: PasswordMode,
+    /// Last login time in seconds since the epoch
+    login_time: Option<i64>,
+    login_history: Vec<LoginRecord>,
+    /// Days until the password expires, negative if it is already expired.
+    /// None if it never expires or polkit denies reading the expiration policy.
+    password_expires_in: Option<i64>,
+    /// State of the systemd-homed home, i.e. "absent" if its storage device is missing
+    home_state: Option<String>,
+}
+
+impl AccountStatus {
+    fn new(user: &UserProxyBlocking) -> Self {
+        Self {
+            locked: user.locked().unwrap_or_default(),
+            account_type: match user.account_type() {
+                Ok(1) => AccountType::Administrator,
+                _ => AccountType::Standard,
+            },
+            password_hint: user.password_hint().ok().filter(|h| !h.is_empty()),
+            password_mode: match user.password_mode() {
+                Ok(1) => PasswordMode::SetAtLogin,
+                Ok(2) => PasswordMode::None,
+                _ => PasswordMode::Regular,
+            },
+            login_time: user.login_time().ok().filter(|t| *t > 0),
+            login_history: user
+                .login_history()
+                .map(|history| history.into_iter().map(LoginRecord::from).collect())
+                .unwrap_or_default(),
+            password_expires_in: password_expires_in(user),
+            home_state: None,
+        }
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+
+    pub fn password_mode(&self) -> PasswordMode {
+        self.password_mode
+    }
+
+    pub fn password_expires_in(&self) -> Option<i64> {
+        self.password_expires_in
+    }
+
+    pub fn home_state(&self) -> Option<&str> {
+        self.home_state.as_deref()
+    }
+}
+
+/// Days until the password of user expires, computed like chage(1).
+/// AccountsService only returns the policy of other users to callers allowed to
+/// administrate accounts, see the [users] section of webkit-greeter.toml.
+fn password_expires_in(user: &UserProxyBlocking) -> Option<i64> {
+    let (_, last_change, _, max_days, _, _) = user
+        .get_password_expiration_policy()
+        .inspect_err(|e| log::debug!("Failed to get password expiration policy: {e}"))
+        .ok()?;
+    // the password never expires, or it has to be changed right now
+    if max_days <= 0 || max_days >= 99999 || last_change <= 0 {
+        return None;
+    }
+    let today = DateTime::now_utc().ok()?.to_unix() / (24 * 60 * 60);
+    Some(last_change + max_days - today)
 }
 
 impl User {
@@ -32,15 +173,22 @@ impl User {
         session: Option<String>,
         uid: Option<u32>,
         user_name: Option<String>,
+        status: AccountStatus,
     ) -> Self {
+        let image = user_name.as_deref().and_then(|name| {
+            Avatar::resolve(icon_file.as_deref(), home_directory.as_deref())
+                .map(|_| Avatar::uri(name))
+        });
         Self {
             home_directory,
             icon_file,
+            image,
             language,
             real_name,
             session,
             uid,
             user_name,
+            status,
         }
     }
     /// HomeDirectory property
@@ -53,6 +201,11 @@ impl User {
         self.icon_file.as_deref()
     }
 
+    /// URI of the resolved avatar
+    pub fn image(&self) -> Option<&str> {
+        self.image.as_deref()
+    }
+
     /// Language property
     pub fn language(&self) -> Option<&str> {
         self.language.as_deref()
@@ -77,67 +230,230 @@ impl User {
     pub fn user_name(&self) -> Option<&str> {
         self.user_name.as_deref()
     }
+
+    /// Locked, AccountType, PasswordMode... properties
+    pub fn status(&self) -> &AccountStatus {
+        &self.status
+    }
+
+    /// Merge the record of systemd-homed, the home directory of such a user is
+    /// not accessible before login, so the avatar comes from the blob directory.
+    fn with_home(self, home: &HomeEntry, record: &serde_json::Value) -> Self {
+        let real_name = self
+            .real_name
+            .filter(|n| !n.is_empty())
+            .or_else(|| Some(home.real_name.clone()).filter(|n| !n.is_empty()));
+        let language = self
+            .language
+            .filter(|l| !l.is_empty())
+            .or_else(|| record["preferredLanguage"].as_str().map(str::to_string));
+        let icon_file = self
+            .icon_file
+            .filter(|f| Path::new(f).is_file())
+            .or_else(|| home_avatar(&home.user_name, record));
+        let status = AccountStatus {
+            locked: self.status.locked
+                || home.state == "locked"
+                || record["locked"].as_bool().unwrap_or_default(),
+            home_state: Some(home.state.clone()),
+            ..self.status
+        };
+        if home.state == "absent" {
+            log::warn!(
+                "Storage device of the home of {} is missing",
+                home.user_name
+            );
+        }
+        Self::new(
+            self.home_directory
+                .or_else(|| Some(home.home_directory.clone())),
+            icon_file,
+            language,
+            real_name,
+            self.session,
+            self.uid.or(Some(home.uid)),
+            self.user_name.or_else(|| Some(home.user_name.clone())),
+            status,
+        )
+    }
+}
+
+/// `[users]` section of webkit-greeter.toml
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UserFilter {
+    /// Usernames that are never listed
+    hidden_users: Vec<String>,
+    /// Members of these groups are never listed
+    hidden_groups: Vec<String>,
+    /// Users whose login shell has one of these file names are never listed
+    hidden_shells: Vec<String>,
+    /// Overrides UID_MIN of /etc/login.defs
+    uid_min: Option<u32>,
+    /// Overrides UID_MAX of /etc/login.defs
+    uid_max: Option<u32>,
+    /// List no users at all, themes have to ask for the username
+    hide_user_list: bool,
+    /// Save the session and language chosen at login into AccountsService
+    remember_choices: bool,
+    /// Let themes create the first administrator account while no users exist
+    first_boot: bool,
+}
+
+impl Default for UserFilter {
+    fn default() -> Self {
+        Self {
+            hidden_users: vec![],
+            hidden_groups: vec![],
+            hidden_shells: vec!["nologin".to_string(), "false".to_string()],
+            uid_min: None,
+            uid_max: None,
+            hide_user_list: false,
+            remember_choices: true,
+            first_boot: false,
+        }
+    }
+}
+
+impl UserFilter {
+    pub fn hide_user_list(&self) -> bool {
+        self.hide_user_list
+    }
+
+    pub fn remember_choices(&self) -> bool {
+        self.remember_choices
+    }
+
+    pub fn first_boot(&self) -> bool {
+        self.first_boot
+    }
+
+    /// UID range of listed users, falls back to /etc/login.defs
+    pub fn uid_range(&self) -> (u32, u32) {
+        match (self.uid_min, self.uid_max) {
+            (Some(min), Some(max)) => (min, max),
+            (min, max) => {
+                let (uid_min, uid_max) = get_uid_minmax();
+                (min.unwrap_or(uid_min), max.unwrap_or(uid_max))
+            }
+        }
+    }
+
+    /// Whether the user should be listed
+    fn permits(&self, user: &User, (uid_min, uid_max): (u32, u32)) -> bool {
+        let Some(name) = user.user_name() else {
+            return false;
+        };
+        // systemd-homed allocates UIDs from 60001 to 60513, above the usual UID_MAX
+        let in_uid_range = user.status.home_state.is_some()
+            || user
+                .uid()
+                .is_some_and(|uid| uid_min <= uid && uid <= uid_max);
+        if !in_uid_range || self.hidden_users.iter().any(|u| u == name) {
+            return false;
+        }
+        let passwd = match pwd::Passwd::from_name(name) {
+            Ok(Some(passwd)) => passwd,
+            _ => return true,
+        };
+        let shell = Path::new(&passwd.shell)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        if self.hidden_shells.iter().any(|s| s == shell) {
+            return false;
+        }
+        if self.hidden_groups.is_empty() {
+            return true;
+        }
+        let groups = user_groups(name, passwd.gid);
+        !self
+            .hidden_groups
+            .iter()
+            .filter_map(|g| group_id(g))
+            .any(|gid| groups.contains(&gid))
+    }
 }
 
 pub struct UserManager {
-    users: Vec<User>,
+    users: RwLock<Vec<User>>,
 }
 
 impl UserManager {
     pub fn instance() -> &'static Self {
         static USER_MANAGER: OnceLock<UserManager> = OnceLock::new();
-        USER_MANAGER.get_or_init(|| {
-            let mut users = match AccountsService::accounts_proxy() {
-                Some(accounts_proxy) => accounts_proxy
-                    .list_cached_users()
-                    .unwrap()
-                    .into_iter()
-                    .map(|o| {
-                        let user = AccountsService::user_proxy(o);
-                        User::new(
-                            user.home_directory().ok(),
-                            user.icon_file().ok(),
-                            user.language().ok(),
-                            user.real_name().ok(),
-                            user.session().ok(),
-                            user.uid().map(|u| u as u32).ok(),
-                            user.user_name().ok(),
-                        )
-                    })
-                    .collect(),
-                None => vec![],
-            };
-            let pwd_users: Vec<_> = pwd::Passwd::iter()
-                .map(|u| {
-                    User::new(
-                        Some(u.dir),
-                        None,
-                        None,
-                        u.gecos,
-                        None,
-                        Some(u.uid),
-                        Some(u.name),
-                    )
-                })
-                .filter(|u| {
-                    let (uid_min, uid_max) = get_uid_minmax();
-                    u.uid().is_some_and(|uid| uid_min <= uid && uid <= uid_max)
-                })
-                .collect();
-
-            let uids: Vec<_> = users.iter().map(|u| u.uid()).collect();
-            users.extend(
-                pwd_users
-                    .into_iter()
-                    .filter(|u| !uids.contains(&u.uid()))
-                    .collect::<Vec<_>>(),
-            );
-            Self { users }
+        USER_MANAGER.get_or_init(|| Self {
+            users: RwLock::new(load_users().unwrap_or_default()),
         })
     }
 
-    pub fn list_users(&self) -> &[User] {
-        &self.users
+    pub fn list_users(&self) -> Vec<User> {
+        self.users.read().unwrap().clone()
+    }
+
+    /// Keep the user list in sync with AccountsService,
+    /// `f` is called on the main context after every change.
+    pub fn connect_users_changed<F: Fn() + 'static>(&'static self, f: F) {
+        MainContext::default().spawn_local(async move {
+            if let Err(e) = self.watch_accounts(f).await {
+                log::error!("Failed to watch accountsservice users: {e}");
+            }
+        });
+    }
+
+    async fn watch_accounts<F: Fn() + 'static>(&'static self, f: F) -> zbus::Result<()> {
+        let conn = system_conn().inner();
+        let accounts = AccountsProxy::new(conn).await?;
+        let added = accounts.receive_user_added().await?.map(|_| ());
+        let deleted = accounts.receive_user_deleted().await?.map(|_| ());
+        let rule = MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .sender("org.freedesktop.Accounts")?
+            .interface("org.freedesktop.Accounts.User")?
+            .member("Changed")?
+            .build();
+        let changed = MessageStream::for_match_rule(rule, conn, None)
+            .await?
+            .map(|_| ());
+        // i.e. a home of systemd-homed is locked, or its storage device is plugged
+        let rule = MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .sender("org.freedesktop.home1")?
+            .interface("org.freedesktop.DBus.Properties")?
+            .member("PropertiesChanged")?
+            .path_namespace("/org/freedesktop/home1/home")?
+            .build();
+        let homes_changed = MessageStream::for_match_rule(rule, conn, None)
+            .await?
+            .map(|_| ());
+
+        let mut events = pin!(stream::select(
+            stream::select(added, deleted),
+            stream::select(changed, homes_changed)
+        ));
+        let f = Rc::new(f);
+        let pending: Rc<Cell<Option<SourceId>>> = Rc::default();
+        while events.next().await.is_some() {
+            log::debug!("Users changed");
+            // a change of one user emits several signals, reload after the last one
+            if let Some(source) = pending.take() {
+                source.remove();
+            }
+            let f = f.clone();
+            let pending_ = pending.clone();
+            pending.set(Some(glib::timeout_add_local_once(
+                USERS_RELOAD_DELAY,
+                move || {
+                    pending_.set(None);
+                    // keep the previous users if AccountsService is restarting
+                    if let Some(users) = load_users() {
+                        *self.users.write().unwrap() = users;
+                        f();
+                    }
+                },
+            )));
+        }
+        Ok(())
     }
 
     pub fn set_language(username: &str, language: &str) -> Result<(), String> {
@@ -149,6 +465,280 @@ impl UserManager {
             None => Err("Failed to connect to accountsservice".to_string()),
         }
     }
+
+    /// Store the session, language and keyboard layout chosen in the greeter into the
+    /// AccountsService record of user, so that they are preselected at the next login.
+    /// The layout is also kept in the state of the greeter.
+    pub fn remember_choices(
+        username: &str,
+        session: Option<&Session>,
+        language: Option<&str>,
+        layout: Option<&Layout>,
+    ) {
+        if !Config::instance().users().remember_choices() {
+            return;
+        }
+        if let Some(layout) = layout {
+            let mut state = GreeterState::load();
+            state.set_layout(username, layout.name());
+            state.save();
+        }
+        let Some(proxy) = AccountsService::accounts_proxy() else {
+            log::warn!("Failed to connect to accountsservice");
+            return;
+        };
+        let (user, display_manager) = match proxy.find_user_by_name(username) {
+            Ok(o) => (
+                AccountsService::user_proxy(o.clone()),
+                AccountsService::display_manager_proxy(o),
+            ),
+            Err(e) => {
+                log::warn!("Failed to find {username} in accountsservice: {e}");
+                return;
+            }
+        };
+        if let Some(session) = session {
+            // other display managers know sessions by desktop file name, not by key
+            let session_type = match session.type_() {
+                "x" => "x11",
+                session_type => session_type,
+            };
+            let mut result = user
+                .set_session(session.id())
+                .and_then(|_| user.set_session_type(session_type));
+            if session.type_() == "x" {
+                result = result.and_then(|_| user.set_xsession(session.id()));
+            }
+            if let Err(e) = result {
+                log::warn!("Failed to save session of {username}: {e}");
+            }
+        }
+        if let Some(language) = language
+            && let Err(e) = user.set_language(language)
+        {
+            log::warn!("Failed to save language of {username}: {e}");
+        }
+        if let Some(layout) = layout {
+            // LightDM separates layout and variant with a tab
+            let chosen = match layout.layout_variant() {
+                (l, "") => l.to_string(),
+                (l, v) => format!("{l}\t{v}"),
+            };
+            let mut layouts = display_manager.xkeyboard_layouts().unwrap_or_default();
+            layouts.retain(|l| *l != chosen);
+            layouts.insert(0, chosen);
+            if let Err(e) = display_manager.set_xkeyboard_layouts(layouts) {
+                log::warn!("Failed to save keyboard layout of {username}: {e}");
+            }
+        }
+    }
+}
+
+impl UserManager {
+    /// Whether the first administrator account has to be created from the greeter,
+    /// which is only the case if enabled and there are no regular users at all.
+    pub fn first_boot_required() -> bool {
+        if !Config::instance().users().first_boot() {
+            return false;
+        }
+        let has_no_users = match AccountsService::has_no_users() {
+            Ok(has_no_users) => has_no_users,
+            Err(e) => {
+                log::error!("Failed to get HasNoUsers of accountsservice: {e}");
+                false
+            }
+        };
+        let (uid_min, uid_max) = Config::instance().users().uid_range();
+        has_no_users && !pwd::Passwd::iter().any(|u| uid_min <= u.uid && u.uid <= uid_max)
+    }
+
+    /// Create the first administrator account, only allowed while first boot is required
+    pub fn create_first_user(
+        username: &str,
+        real_name: &str,
+        password: &str,
+    ) -> Result<(), String> {
+        if !is_valid_username(username) {
+            return Err(format!("Invalid username: {username:?}"));
+        }
+        if password.is_empty() {
+            return Err("Password must not be empty".to_string());
+        }
+        if !Self::first_boot_required() {
+            return Err("First boot user creation is not available".to_string());
+        }
+        let Some(proxy) = AccountsService::accounts_proxy() else {
+            return Err("Failed to connect to accountsservice".to_string());
+        };
+        let hashed = hash_password(password)?;
+        // 1 is ACCOUNT_TYPE_ADMINISTRATOR
+        let user = proxy
+            .create_user(username, real_name, 1)
+            .map(AccountsService::user_proxy)
+            .map_err(|e| e.to_string())?;
+        user.set_password(&hashed, "").map_err(|e| e.to_string())?;
+        log::info!("Created the first user {username}");
+        Ok(())
+    }
+}
+
+/// Users of AccountsService, pwd and systemd-homed, None if AccountsService fails to list them
+fn load_users() -> Option<Vec<User>> {
+    let mut users = match AccountsService::accounts_proxy() {
+        Some(accounts_proxy) => accounts_proxy
+            .list_cached_users()
+            .inspect_err(|e| log::error!("Failed to list accountsservice users: {e}"))
+            .ok()?
+            .into_iter()
+            .map(|o| {
+                let user = AccountsService::user_proxy(o);
+                User::new(
+                    user.home_directory().ok(),
+                    user.icon_file().ok(),
+                    user.language().ok(),
+                    user.real_name().ok(),
+                    user.session().ok().map(|s| {
+                        SessionManager::session_key(&s, user.session_type().ok().as_deref())
+                    }),
+                    user.uid().map(|u| u as u32).ok(),
+                    user.user_name().ok(),
+                    AccountStatus::new(&user),
+                )
+            })
+            .collect(),
+        None => vec![],
+    };
+    let filter = Config::instance().users();
+    let uid_range = filter.uid_range();
+    let pwd_users: Vec<_> = pwd::Passwd::iter()
+        .map(|u| {
+            User::new(
+                Some(u.dir),
+                None,
+                None,
+                u.gecos,
+                None,
+                Some(u.uid),
+                Some(u.name),
+                AccountStatus::default(),
+            )
+        })
+        .filter(|u| filter.permits(u, uid_range))
+        .collect();
+
+    let uids: Vec<_> = users.iter().map(|u| u.uid()).collect();
+    users.extend(
+        pwd_users
+            .into_iter()
+            .filter(|u| !uids.contains(&u.uid()))
+            .collect::<Vec<_>>(),
+    );
+    for (home, record) in load_homes() {
+        match users
+            .iter()
+            .position(|u| u.user_name() == Some(home.user_name.as_str()))
+        {
+            Some(i) => {
+                let user = users.remove(i);
+                users.insert(i, user.with_home(&home, &record));
+            }
+            None => {
+                let user = User::new(
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    AccountStatus::default(),
+                );
+                users.push(user.with_home(&home, &record));
+            }
+        }
+    }
+    users.retain(|u| filter.permits(u, uid_range));
+    Some(users)
+}
+
+/// Homes managed by systemd-homed, with their public user record
+fn load_homes() -> Vec<(HomeEntry, serde_json::Value)> {
+    let Some(manager) = HomedManager::proxy() else {
+        return vec![];
+    };
+    match manager.list_homes() {
+        Ok(homes) => homes
+            .into_iter()
+            .map(|home| {
+                let record = manager
+                    .get_user_record_by_name(&home.user_name)
+                    .ok()
+                    .and_then(|(record, _, _)| serde_json::from_str(&record).ok())
+                    .unwrap_or_default();
+                (home, record)
+            })
+            .collect(),
+        Err(e) => {
+            log::debug!("Failed to list systemd-homed homes: {e}");
+            vec![]
+        }
+    }
+}
+
+/// Avatar in the blob directory of a systemd-homed user
+fn home_avatar(username: &str, record: &serde_json::Value) -> Option<String> {
+    let blob_dir = record["blobDirectory"]
+        .as_str()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new("/var/cache/systemd/home").join(username));
+    let avatar = blob_dir.join("avatar");
+    avatar
+        .is_file()
+        .then(|| avatar.to_string_lossy().into_owned())
+}
+
+/// Same rules as the default NAME_REGEX of useradd
+fn is_valid_username(username: &str) -> bool {
+    let mut chars = username.chars();
+    username.len() <= 32
+        && chars
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+/// Hash password with SHA-512 crypt, as SetPassword expects
+fn hash_password(password: &str) -> Result<String, String> {
+    pwhash::sha512_crypt::hash(password).map_err(|e| e.to_string())
+}
+
+fn group_id(group: &str) -> Option<u32> {
+    let group = CString::new(group).ok()?;
+    // SAFETY: group is a valid C string, the returned entry is read before the next call
+    let entry = unsafe { libc::getgrnam(group.as_ptr()) };
+    // SAFETY: entry is checked for null
+    (!entry.is_null()).then(|| unsafe { (*entry).gr_gid })
+}
+
+/// Primary and supplementary groups of user
+fn user_groups(username: &str, gid: u32) -> Vec<u32> {
+    let Ok(name) = CString::new(username) else {
+        return vec![gid];
+    };
+    let mut ngroups: libc::c_int = 32;
+    loop {
+        let mut groups = vec![0; ngroups as usize];
+        // SAFETY: groups has room for ngroups entries
+        let ret =
+            unsafe { libc::getgrouplist(name.as_ptr(), gid, groups.as_mut_ptr(), &mut ngroups) };
+        if ret >= 0 {
+            groups.truncate(ngroups as usize);
+            return groups;
+        }
+        if ngroups as usize <= groups.len() {
+            return vec![gid];
+        }
+    }
 }
 
 fn get_uid_minmax() -> (u32, u32) {
@@ -180,3 +770,6 @@ fn get_uid_minmax() -> (u32, u32) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
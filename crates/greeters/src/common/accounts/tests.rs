@@ -0,0 +1,213 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use webkit::glib::find_program_in_path;
+use zbus::{
+    blocking::connection, fdo, interface, object_server::ObjectServer, zvariant::OwnedObjectPath,
+};
+
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+};
+
+use super::{UserManager, hash_password, is_valid_username};
+use crate::config::Config;
+
+#[derive(Debug)]
+struct MockUserData {
+    name: String,
+    real_name: String,
+    account_type: i32,
+    password: Option<String>,
+}
+
+type MockState = Arc<Mutex<Vec<MockUserData>>>;
+
+fn user_path(index: usize) -> OwnedObjectPath {
+    OwnedObjectPath::try_from(format!("/org/freedesktop/Accounts/User{}", 1000 + index)).unwrap()
+}
+
+/// The part of org.freedesktop.Accounts used by the first boot mode
+struct MockAccounts(MockState);
+
+#[interface(name = "org.freedesktop.Accounts")]
+impl MockAccounts {
+    async fn create_user(
+        &self,
+        name: &str,
+        fullname: &str,
+        account_type: i32,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> fdo::Result<OwnedObjectPath> {
+        let index = {
+            let mut users = self.0.lock().unwrap();
+            if users.iter().any(|u| u.name == name) {
+                return Err(fdo::Error::Failed(format!("{name} already exists")));
+            }
+            users.push(MockUserData {
+                name: name.to_string(),
+                real_name: fullname.to_string(),
+                account_type,
+                password: None,
+            });
+            users.len() - 1
+        };
+        let user = MockUser {
+            state: self.0.clone(),
+            index,
+        };
+        server.at(user_path(index), user).await?;
+        Ok(user_path(index))
+    }
+
+    fn find_user_by_name(&self, name: &str) -> fdo::Result<OwnedObjectPath> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .position(|u| u.name == name)
+            .map(user_path)
+            .ok_or_else(|| fdo::Error::Failed(format!("{name} does not exist")))
+    }
+
+    fn list_cached_users(&self) -> Vec<OwnedObjectPath> {
+        (0..self.0.lock().unwrap().len()).map(user_path).collect()
+    }
+
+    #[zbus(property)]
+    fn has_no_users(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+}
+
+struct MockUser {
+    state: MockState,
+    index: usize,
+}
+
+#[interface(name = "org.freedesktop.Accounts.User")]
+impl MockUser {
+    fn set_password(&self, password: &str, _hint: &str) {
+        self.state.lock().unwrap()[self.index].password = Some(password.to_string());
+    }
+
+    #[zbus(property)]
+    fn user_name(&self) -> String {
+        self.state.lock().unwrap()[self.index].name.clone()
+    }
+
+    #[zbus(property)]
+    fn real_name(&self) -> String {
+        self.state.lock().unwrap()[self.index].real_name.clone()
+    }
+
+    #[zbus(property)]
+    fn account_type(&self) -> i32 {
+        self.state.lock().unwrap()[self.index].account_type
+    }
+}
+
+fn verify_password(password: &str, hashed: &str) -> bool {
+    pwhash::unix::verify(password, hashed)
+}
+
+#[test]
+fn username_validation() {
+    assert!(is_valid_username("admin"));
+    assert!(is_valid_username("_build-user1"));
+    assert!(!is_valid_username(""));
+    assert!(!is_valid_username("1admin"));
+    assert!(!is_valid_username("Admin"));
+    assert!(!is_valid_username("../admin"));
+    assert!(!is_valid_username(&"a".repeat(33)));
+}
+
+#[test]
+fn password_is_hashed_with_salt() {
+    let first = hash_password("correct horse").unwrap();
+    let second = hash_password("correct horse").unwrap();
+    assert!(first.starts_with('$'));
+    assert_ne!(first, second);
+    assert!(verify_password("correct horse", &first));
+    assert!(!verify_password("battery staple", &first));
+}
+
+/// Set in the child process that runs [first_boot_creates_administrator] for real
+const FIRST_BOOT_CHILD: &str = "WEBKIT_GREETER_FIRST_BOOT_CHILD";
+
+/// Runs against a mock AccountsService on a private bus, which is used as the system bus.
+/// The system bus address, the config and the system connection are process wide, so the
+/// test runs itself again in a child process to keep them away from the other tests.
+#[test]
+fn first_boot_creates_administrator() {
+    if std::env::var_os(FIRST_BOOT_CHILD).is_none() {
+        if find_program_in_path("dbus-daemon").is_none() {
+            eprintln!("Skipped, dbus-daemon is not in PATH");
+            return;
+        }
+        let name = concat!(module_path!(), "::first_boot_creates_administrator");
+        let name = name.split_once("::").map_or(name, |(_, name)| name);
+        let status = Command::new(std::env::current_exe().unwrap())
+            .args([name, "--exact", "--nocapture"])
+            .env(FIRST_BOOT_CHILD, "1")
+            .status()
+            .unwrap();
+        assert!(status.success(), "first boot test failed: {status}");
+        return;
+    }
+
+    let mut daemon = Command::new("dbus-daemon")
+        .args(["--session", "--nofork", "--print-address"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn dbus-daemon");
+    let mut address = String::new();
+    BufReader::new(daemon.stdout.take().unwrap())
+        .read_line(&mut address)
+        .unwrap();
+    let address = address.trim();
+    // SAFETY: this child process runs no other test
+    unsafe { std::env::set_var("DBUS_SYSTEM_BUS_ADDRESS", address) };
+
+    let state = MockState::default();
+    let _accounts = connection::Builder::address(address)
+        .unwrap()
+        .name("org.freedesktop.Accounts")
+        .unwrap()
+        .serve_at("/org/freedesktop/Accounts", MockAccounts(state.clone()))
+        .unwrap()
+        .build()
+        .unwrap();
+    // a UID range nobody of the host is in, so only the mock decides about first boot
+    let config = serde_json::json!({
+        "users": { "first_boot": true, "uid_min": 4000000000u32, "uid_max": 4000000000u32 }
+    });
+    Config::init(serde_json::from_value(config).unwrap());
+
+    assert!(UserManager::first_boot_required());
+    assert!(UserManager::create_first_user("Admin", "Admin", "secret").is_err());
+    assert!(UserManager::create_first_user("admin", "Admin", "").is_err());
+    assert!(state.lock().unwrap().is_empty());
+
+    UserManager::create_first_user("admin", "Administrator", "secret").unwrap();
+    {
+        let users = state.lock().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].name, "admin");
+        assert_eq!(users[0].real_name, "Administrator");
+        assert_eq!(users[0].account_type, 1);
+        let hashed = users[0].password.as_deref().unwrap();
+        assert_ne!(hashed, "secret");
+        assert!(verify_password("secret", hashed));
+    }
+
+    assert!(!UserManager::first_boot_required());
+    assert!(UserManager::create_first_user("intruder", "Intruder", "secret").is_err());
+    assert_eq!(state.lock().unwrap().len(), 1);
+
+    daemon.kill().unwrap();
+    daemon.wait().unwrap();
+}
@@ -0,0 +1,65 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use super::UserManager;
+use crate::config::Config;
+
+/// URI scheme under which avatars are served to themes,
+/// it is allowed by the web extension even in secure mode.
+pub const AVATAR_URI_PREFIX: &str = "webkit-greeter://avatar/";
+
+pub struct Avatar;
+
+impl Avatar {
+    /// URI of the avatar of user, served by the greeter's URI scheme handler
+    pub fn uri(username: &str) -> String {
+        format!("{AVATAR_URI_PREFIX}{username}")
+    }
+
+    /// Avatar image of a user listed by the greeter
+    pub fn path(username: &str) -> Option<PathBuf> {
+        UserManager::instance()
+            .list_users()
+            .into_iter()
+            .find(|u| u.user_name() == Some(username))
+            .and_then(|u| Self::resolve(u.icon_file(), u.home_directory()))
+    }
+
+    /// Resolve the avatar image of a user, in order of:
+    /// 1. IconFile of AccountsService
+    /// 2. ~/.face or ~/.face.icon
+    /// 3. `user_image` of the `[branding]` section
+    ///
+    /// The greeter user can only read ~/.face of homes that are readable by others,
+    /// not of private, encrypted or systemd-homed ones. AccountsService copies the icon
+    /// set by a user to a place readable by everyone, so that one is found instead.
+    pub fn resolve(icon_file: Option<&str>, home_directory: Option<&str>) -> Option<PathBuf> {
+        icon_file
+            .map(PathBuf::from)
+            .filter(|path| is_readable(path))
+            .or_else(|| face_path(Path::new(home_directory?)))
+            .or_else(|| {
+                Config::instance()
+                    .default_avatar()
+                    .map(PathBuf::from)
+                    .filter(|path| is_readable(path))
+            })
+    }
+}
+
+fn face_path(home: &Path) -> Option<PathBuf> {
+    [".face", ".face.icon"]
+        .into_iter()
+        .map(|face| home.join(face))
+        .find(|path| is_readable(path))
+}
+
+fn is_readable(path: &Path) -> bool {
+    path.is_file() && File::open(path).is_ok()
+}
@@ -5,7 +5,8 @@
 use serde::{Deserialize, Serialize};
 use zbus::{
     proxy,
-    zvariant::{OwnedObjectPath, OwnedValue, Type, Value, as_value},
+    proxy::CacheProperties,
+    zvariant::{OwnedObjectPath, OwnedValue, Type, Value},
 };
 
 use std::{collections::HashMap, sync::OnceLock};
@@ -19,6 +20,14 @@ impl AccountsService {
         ACCOUNTS_PROXY.get_or_init(|| AccountsProxyBlocking::new(system_conn()).ok())
     }
 
+    /// HasNoUsers property, read without the property cache of accounts_proxy
+    pub fn has_no_users() -> zbus::Result<bool> {
+        AccountsProxyBlocking::builder(system_conn())
+            .cache_properties(CacheProperties::No)
+            .build()?
+            .has_no_users()
+    }
+
     pub fn user_proxy(o: OwnedObjectPath) -> UserProxyBlocking<'static> {
         UserProxyBlocking::builder(system_conn())
             .path(o)
@@ -26,6 +35,15 @@ impl AccountsService {
             .build()
             .unwrap()
     }
+
+    /// Display manager extension of the user, provided by the AccountsService of LightDM
+    pub fn display_manager_proxy(o: OwnedObjectPath) -> DisplayManagerUserProxyBlocking<'static> {
+        DisplayManagerUserProxyBlocking::builder(system_conn())
+            .path(o)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
 }
 
 #[proxy(
@@ -88,15 +106,12 @@ pub trait Accounts {
     fn has_no_users(&self) -> zbus::Result<bool>;
 }
 
+/// Entry of the LoginHistory property, (login_time, logout_time, extra)
 #[derive(Debug, Type, Value, OwnedValue, Deserialize, Serialize)]
-#[zvariant(signature = "dict")]
-struct LoginHistory {
-    #[serde(with = "as_value")]
-    login_time: i64,
-    #[serde(with = "as_value")]
-    logout_time: i64,
-    #[serde(flatten)]
-    extra: HashMap<String, OwnedValue>,
+pub struct LoginHistory {
+    pub login_time: i64,
+    pub logout_time: i64,
+    pub extra: HashMap<String, OwnedValue>,
 }
 
 #[proxy(
@@ -274,3 +289,16 @@ pub trait User {
     #[zbus(property, name = "XSession")]
     fn xsession(&self) -> zbus::Result<String>;
 }
+
+#[proxy(
+    interface = "org.freedesktop.DisplayManager.AccountsService",
+    default_service = "org.freedesktop.Accounts"
+)]
+pub trait DisplayManagerUser {
+    /// XKeyboardLayouts property, layouts like "us" or "us\tdvorak"
+    #[zbus(property, name = "XKeyboardLayouts")]
+    fn xkeyboard_layouts(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property, name = "XKeyboardLayouts")]
+    fn set_xkeyboard_layouts(&self, layouts: Vec<String>) -> zbus::Result<()>;
+}
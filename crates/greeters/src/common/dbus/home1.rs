@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, Type},
+};
+
+use std::sync::OnceLock;
+
+use super::system_conn;
+
+pub struct HomedManager;
+impl HomedManager {
+    pub fn proxy() -> &'static Option<ManagerProxyBlocking<'static>> {
+        static MANAGER: OnceLock<Option<ManagerProxyBlocking>> = OnceLock::new();
+        MANAGER.get_or_init(|| ManagerProxyBlocking::new(system_conn()).ok())
+    }
+}
+
+/// Entry of ListHomes
+#[derive(Debug, Type, Serialize, Deserialize)]
+pub struct HomeEntry {
+    pub user_name: String,
+    pub uid: u32,
+    /// "active", "inactive", "absent", "locked", "dirty"...
+    pub state: String,
+    pub gid: u32,
+    pub real_name: String,
+    pub home_directory: String,
+    pub shell: String,
+    pub object_path: OwnedObjectPath,
+}
+
+#[proxy(
+    interface = "org.freedesktop.home1.Manager",
+    default_service = "org.freedesktop.home1",
+    default_path = "/org/freedesktop/home1"
+)]
+pub trait Manager {
+    /// GetUserRecordByName method
+    fn get_user_record_by_name(
+        &self,
+        user_name: &str,
+    ) -> zbus::Result<(String, bool, OwnedObjectPath)>;
+
+    /// ListHomes method
+    fn list_homes(&self) -> zbus::Result<Vec<HomeEntry>>;
+}
@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use zbus::proxy;
+
+use std::sync::OnceLock;
+
+use super::system_conn;
+
+pub struct LocaledManager;
+impl LocaledManager {
+    pub fn proxy() -> &'static Option<Locale1ProxyBlocking<'static>> {
+        static LOCALE1: OnceLock<Option<Locale1ProxyBlocking>> = OnceLock::new();
+        LOCALE1.get_or_init(|| Locale1ProxyBlocking::new(system_conn()).ok())
+    }
+}
+
+#[proxy(
+    interface = "org.freedesktop.locale1",
+    default_service = "org.freedesktop.locale1",
+    default_path = "/org/freedesktop/locale1"
+)]
+pub trait Locale1 {
+    /// Locale property
+    #[zbus(property)]
+    fn locale(&self) -> zbus::Result<Vec<String>>;
+
+    /// VConsoleKeymap property
+    #[zbus(property, name = "VConsoleKeymap")]
+    fn vconsole_keymap(&self) -> zbus::Result<String>;
+
+    /// X11Layout property, i.e. "us,de"
+    #[zbus(property, name = "X11Layout")]
+    fn x11_layout(&self) -> zbus::Result<String>;
+
+    /// X11Model property
+    #[zbus(property, name = "X11Model")]
+    fn x11_model(&self) -> zbus::Result<String>;
+
+    /// X11Options property, i.e. "grp:alt_shift_toggle"
+    #[zbus(property, name = "X11Options")]
+    fn x11_options(&self) -> zbus::Result<String>;
+
+    /// X11Variant property, i.e. ",nodeadkeys"
+    #[zbus(property, name = "X11Variant")]
+    fn x11_variant(&self) -> zbus::Result<String>;
+}
@@ -5,7 +5,10 @@
 use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
-use zbus::{proxy, zvariant::Type};
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, Type},
+};
 
 use super::system_conn;
 
@@ -15,6 +18,12 @@ impl LogindManager {
         static MANAGER: OnceLock<ManagerProxyBlocking> = OnceLock::new();
         MANAGER.get_or_init(|| ManagerProxyBlocking::new(system_conn()).unwrap())
     }
+
+    pub fn session_proxy(o: OwnedObjectPath) -> zbus::Result<SessionProxyBlocking<'static>> {
+        SessionProxyBlocking::builder(system_conn())
+            .path(o)?
+            .build()
+    }
 }
 
 #[allow(unused)]
@@ -40,19 +49,18 @@ struct Session {
     object_path: zbus::zvariant::OwnedObjectPath,
 }
 
-#[allow(unused)]
 #[derive(Debug, Type, Serialize, Deserialize)]
-struct SessionExt {
-    session_id: String,
-    uid: u32,
-    user_name: String,
-    seat_id: String,
-    pid: u32,
-    class: String,
-    tty: String,
-    idle: bool,
-    idle_timestamp: u64,
-    object_path: zbus::zvariant::OwnedObjectPath,
+pub struct SessionExt {
+    pub session_id: String,
+    pub uid: u32,
+    pub user_name: String,
+    pub seat_id: String,
+    pub pid: u32,
+    pub class: String,
+    pub tty: String,
+    pub idle: bool,
+    pub idle_timestamp: u64,
+    pub object_path: zbus::zvariant::OwnedObjectPath,
 }
 
 #[allow(unused)]
@@ -581,3 +589,47 @@ pub trait Manager {
     #[zbus(property)]
     fn set_wall_message(&self, value: &str) -> zbus::Result<()>;
 }
+
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+pub trait Session {
+    /// Activate method
+    fn activate(&self) -> zbus::Result<()>;
+
+    /// SetIdleHint method
+    fn set_idle_hint(&self, idle: bool) -> zbus::Result<()>;
+
+    /// Active property
+    #[zbus(property)]
+    fn active(&self) -> zbus::Result<bool>;
+
+    /// Class property
+    #[zbus(property)]
+    fn class(&self) -> zbus::Result<String>;
+
+    /// IdleHint property
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    /// Id property
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    /// Name property
+    #[zbus(property)]
+    fn name(&self) -> zbus::Result<String>;
+
+    /// State property
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+
+    /// Type property
+    #[zbus(property, name = "Type")]
+    fn type_(&self) -> zbus::Result<String>;
+
+    /// VTNr property
+    #[zbus(property, name = "VTNr")]
+    fn vtnr(&self) -> zbus::Result<u32>;
+}
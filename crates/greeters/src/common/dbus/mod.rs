@@ -3,16 +3,23 @@
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
 mod accountsservice;
+mod home1;
+mod locale1;
 mod logind;
 
-pub use accountsservice::AccountsService;
-pub use logind::LogindManager;
+pub use accountsservice::{
+    AccountsProxy, AccountsService, DisplayManagerUserProxyBlocking, LoginHistory,
+    UserProxyBlocking,
+};
+pub use home1::{HomeEntry, HomedManager};
+pub use locale1::LocaledManager;
+pub use logind::{LogindManager, SessionExt};
 
 use zbus::blocking::Connection;
 
 use std::sync::OnceLock;
 
-fn system_conn() -> &'static Connection {
+pub(super) fn system_conn() -> &'static Connection {
     static SYSTEM_CONN: OnceLock<Connection> = OnceLock::new();
     SYSTEM_CONN.get_or_init(|| Connection::system().unwrap())
 }
@@ -3,15 +3,30 @@
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
 use gettextrs::{LocaleCategory, dgettext, setlocale};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use std::{ffi::CStr, process::Command, sync::OnceLock};
+use std::{
+    ffi::{CStr, CString},
+    fs::File,
+    os::unix::fs::FileExt,
+    path::Path,
+    sync::{OnceLock, RwLock},
+};
+
+/// Language chosen by set_greeter_language, overrides $LANG
+static GREETER_LANGUAGE: RwLock<Option<String>> = RwLock::new(None);
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Language {
     code: String,
     name: String,
     territory: String,
+    /// Whether the locale is generated, otherwise it is only available to be generated
+    installed: bool,
+    /// Name of the language in itself, i.e. "日本語" for ja_JP
+    native_name: Option<String>,
+    /// Name of the territory in the language itself
+    native_territory: Option<String>,
 }
 
 impl Language {
@@ -26,12 +41,125 @@ impl Language {
     pub fn territory(&self) -> &str {
         &self.territory
     }
+
+    pub fn installed(&self) -> bool {
+        self.installed
+    }
+
+    pub fn native_name(&self) -> Option<&str> {
+        self.native_name.as_deref()
+    }
+
+    pub fn native_territory(&self) -> Option<&str> {
+        self.native_territory.as_deref()
+    }
+
+    /// Copy with name and territory translated into the message locale of the greeter
+    pub fn localized(&self) -> Language {
+        Language {
+            name: dgettext("iso_639-3", &self.name),
+            territory: if self.territory.is_empty() {
+                String::new()
+            } else {
+                dgettext("iso_3166-1", &self.territory)
+            },
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageSort {
+    /// By locale code
+    #[default]
+    Code,
+    /// By name in the language of the greeter
+    Name,
+    /// By name of the language in itself
+    NativeName,
+}
+
+/// `[languages]` section of webkit-greeter.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LanguageOrder {
+    /// Locales listed right after the system default, i.e. ["en_US", "ja_JP.UTF-8"]
+    preferred: Vec<String>,
+    /// Order of the other languages
+    sort_by: LanguageSort,
+}
+
+impl LanguageOrder {
+    /// Position of the preferred locale matching code
+    fn preference(&self, code: &str) -> Option<usize> {
+        self.preferred
+            .iter()
+            .position(|p| same_locale(p, code) || p == code)
+    }
+
+    /// Sort localized languages: the system default, the preferred ones, then the others
+    pub fn sort(&self, languages: &mut [Language]) {
+        let system_default = system_language();
+        let rank = |l: &Language| {
+            if system_default
+                .as_deref()
+                .is_some_and(|d| same_locale(d, &l.code))
+            {
+                (0, 0)
+            } else if let Some(i) = self.preference(&l.code) {
+                (1, i)
+            } else {
+                (2, 0)
+            }
+        };
+        languages.sort_by(|a, b| {
+            rank(a).cmp(&rank(b)).then_with(|| match self.sort_by {
+                LanguageSort::Code => a.code.cmp(&b.code),
+                LanguageSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                LanguageSort::NativeName => {
+                    let native = |l: &Language| l.native_name.clone().unwrap_or(l.name.clone());
+                    native(a).to_lowercase().cmp(&native(b).to_lowercase())
+                }
+            })
+        });
+    }
+}
+
+/// Whether both codes name the same locale, ignoring the codeset, i.e. "de_DE" and "de_DE.UTF-8"
+fn same_locale(a: &str, b: &str) -> bool {
+    let strip = |code: &str| {
+        let (name, modifier) = code.split_once('@').unwrap_or((code, ""));
+        (
+            name.split('.').next().unwrap().to_string(),
+            modifier.to_string(),
+        )
+    };
+    strip(a) == strip(b)
+}
+
+/// LANG of /etc/locale.conf, or of the greeter process
+fn system_language() -> Option<String> {
+    std::fs::read_to_string("/etc/locale.conf")
+        .ok()
+        .and_then(|content| {
+            content.lines().find_map(|line| {
+                line.trim()
+                    .strip_prefix("LANG=")
+                    .map(|lang| lang.trim_matches('"').to_string())
+            })
+        })
+        .or_else(|| std::env::var("LANG").ok())
+        .filter(|lang| !lang.is_empty())
 }
 
 pub struct LanguageManager;
 impl LanguageManager {
     pub fn current() -> Option<Language> {
-        let lang = std::env::var("LANG");
+        let lang = match Self::greeter_language() {
+            Some(code) => Ok(code),
+            None => std::env::var("LANG"),
+        };
         match lang {
             Ok(code) => {
                 let prefix = code.split('.').next();
@@ -44,62 +172,264 @@ impl LanguageManager {
         }
     }
 
+    /// Language chosen by set_greeter_language
+    pub fn greeter_language() -> Option<String> {
+        GREETER_LANGUAGE.read().unwrap().clone()
+    }
+
+    /// Switch the message locale of the greeter, which is used by `Language::localized`,
+    /// `Session::localized`, `Layout::localized` and the dialogs of the greeter.
+    pub fn set_greeter_language(code: &str) -> Option<Language> {
+        let prefix = code.split('.').next();
+        let language = Self::languages()
+            .iter()
+            .find(|l| l.installed && l.code.split('.').next() == prefix)?
+            .clone();
+        if setlocale(LocaleCategory::LcMessages, language.code()).is_none() {
+            log::error!("Failed to set the message locale to {}", language.code());
+            return None;
+        }
+        *GREETER_LANGUAGE.write().unwrap() = Some(language.code.clone());
+        Some(language)
+    }
+
+    /// Languages with their English names, see `Language::localized`
     pub fn languages() -> &'static [Language] {
         static LANGUAGES: OnceLock<Vec<Language>> = OnceLock::new();
         LANGUAGES.get_or_init(|| {
-            const _NL_IDENTIFICATION_LANGUAGE: i32 = 786439;
-            const _NL_IDENTIFICATION_TERRITORY: i32 = 786440;
             gettextrs::bindtextdomain("iso_639-3", "/usr/share/locale").unwrap();
             gettextrs::bindtextdomain("iso_3166-1", "/usr/share/locale").unwrap();
 
-            match Command::new("locale").arg("-a").output() {
-                Ok(output) => String::from_utf8(output.stdout)
-                    .expect("The output of 'locale -a' is not encoded as utf8")
-                    .split('\n')
-                    .filter(|code| code.ends_with(".utf8"))
-                    .map(|code| {
-                        let current = setlocale(LocaleCategory::LcAll, []).unwrap();
-
-                        setlocale(LocaleCategory::LcIdentification, code);
-                        setlocale(LocaleCategory::LcMessages, "");
-
-                        let language_en = unsafe {
-                            CStr::from_ptr(libc::nl_langinfo(_NL_IDENTIFICATION_LANGUAGE))
-                                .to_string_lossy()
-                                .to_string()
-                        };
-                        let name = if language_en.is_empty() {
-                            code.split(['_', '.', '@']).next().unwrap().to_string()
-                        } else {
-                            dgettext("iso_639-3", &language_en)
-                        };
-                        let territory_en = unsafe {
-                            CStr::from_ptr(libc::nl_langinfo(_NL_IDENTIFICATION_TERRITORY))
-                                .to_string_lossy()
-                                .to_string()
-                        };
-
-                        let territory = if !code.contains('_') {
-                            String::new()
-                        } else if territory_en.is_empty() {
+            let installed: Vec<_> = installed_locales()
+                .iter()
+                .filter_map(|name| normalize_locale(name))
+                .collect();
+            let mut codes = installed.clone();
+            codes.extend(
+                available_locales()
+                    .iter()
+                    .filter_map(|name| normalize_locale(name)),
+            );
+            codes.sort();
+            codes.dedup();
+            codes
+                .into_iter()
+                .map(|code| {
+                    let is_installed = installed.contains(&code);
+                    let (language_en, territory_en) = identification(&code, is_installed);
+                    let name = language_en
+                        .unwrap_or_else(|| code.split(['_', '.', '@']).next().unwrap().to_string());
+                    let territory = if !code.contains('_') {
+                        String::new()
+                    } else {
+                        territory_en.unwrap_or_else(|| {
                             code.split(['_', '.', '@']).nth(1).unwrap().to_string()
-                        } else {
-                            dgettext("iso_3166-1", &territory_en)
-                        };
-
-                        setlocale(LocaleCategory::LcAll, current);
-                        Language {
-                            code: code.to_string(),
-                            name,
-                            territory,
-                        }
+                        })
+                    };
+                    let (native_name, native_territory) = if is_installed {
+                        native_names(&code, &name, &territory)
+                    } else {
+                        (None, None)
+                    };
+                    Language {
+                        code,
+                        name,
+                        territory,
+                        installed: is_installed,
+                        native_name,
+                        native_territory,
+                    }
+                })
+                .collect()
+        })
+    }
+}
+
+const LOCALE_DIR: &str = "/usr/lib/locale";
+const LOCALE_ARCHIVE: &str = "/usr/lib/locale/locale-archive";
+const LOCALE_ARCHIVE_MAGIC: u32 = 0xde020109;
+const LOCALE_SOURCES_DIR: &str = "/usr/share/i18n/locales";
+/// Locales that can be generated, the first file exists on Debian and Fedora,
+/// the second one on Arch Linux and Gentoo
+const SUPPORTED_LOCALES: [&str; 2] = ["/usr/share/i18n/SUPPORTED", "/etc/locale.gen"];
+
+/// Turn "de_DE.utf8", "de_DE.UTF-8"... into "de_DE.UTF-8", other charsets are skipped
+fn normalize_locale(name: &str) -> Option<String> {
+    let (name, modifier) = match name.split_once('@') {
+        Some((name, modifier)) => (name, Some(modifier)),
+        None => (name, None),
+    };
+    let (language, codeset) = name.split_once('.')?;
+    if language.is_empty()
+        || language == "C"
+        || language == "POSIX"
+        || codeset.replace('-', "").to_lowercase() != "utf8"
+    {
+        return None;
+    }
+    Some(match modifier {
+        Some(modifier) => format!("{language}.UTF-8@{modifier}"),
+        None => format!("{language}.UTF-8"),
+    })
+}
+
+/// Locales compiled into the locale archive or into directories of /usr/lib/locale
+fn installed_locales() -> Vec<String> {
+    let mut locales = match archive_locales() {
+        Ok(locales) => locales,
+        Err(e) => {
+            log::debug!("Failed to read {LOCALE_ARCHIVE}: {e}");
+            vec![]
+        }
+    };
+    if let Ok(entries) = std::fs::read_dir(LOCALE_DIR) {
+        locales.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().join("LC_IDENTIFICATION").is_file())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned()),
+        );
+    }
+    locales
+}
+
+/// Names in the hash table of the locale archive, see locarfile.h of glibc
+fn archive_locales() -> std::io::Result<Vec<String>> {
+    let file = File::open(LOCALE_ARCHIVE)?;
+    let field = |buf: &[u8], i: usize| {
+        u32::from_ne_bytes(buf[i * 4..i * 4 + 4].try_into().unwrap()) as usize
+    };
+
+    // magic, serial, namehash_offset, namehash_used, namehash_size, string_offset, string_used
+    let mut header = [0u8; 28];
+    file.read_exact_at(&mut header, 0)?;
+    if field(&header, 0) != LOCALE_ARCHIVE_MAGIC as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a locale archive",
+        ));
+    }
+    let (namehash_offset, namehash_size) = (field(&header, 2), field(&header, 4));
+    let (string_offset, string_used) = (field(&header, 5), field(&header, 6));
+
+    // entries of hashval, name_offset, locrec_offset
+    let mut namehash = vec![0u8; namehash_size * 12];
+    file.read_exact_at(&mut namehash, namehash_offset as u64)?;
+    let mut strings = vec![0u8; string_used];
+    file.read_exact_at(&mut strings, string_offset as u64)?;
+
+    Ok(namehash
+        .chunks_exact(12)
+        .filter(|entry| field(entry, 1) != 0 && field(entry, 2) != 0)
+        .filter_map(|entry| {
+            let name = strings.get(field(entry, 1).checked_sub(string_offset)?..)?;
+            let name = CStr::from_bytes_until_nul(name).ok()?;
+            Some(name.to_string_lossy().into_owned())
+        })
+        .collect())
+}
+
+/// Locales listed by SUPPORTED or locale.gen, commented lines of locale.gen included
+fn available_locales() -> Vec<String> {
+    SUPPORTED_LOCALES
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.trim_start_matches(['#', ' ', '\t']).split_whitespace();
+                    let (name, charset) = (fields.next()?, fields.next()?);
+                    if charset != "UTF-8" || fields.next().is_some() {
+                        return None;
+                    }
+                    // "aa_ER UTF-8" is generated as "aa_ER.UTF-8"
+                    Some(match name.split_once('@') {
+                        _ if name.contains('.') => name.to_string(),
+                        Some((name, modifier)) => format!("{name}.UTF-8@{modifier}"),
+                        None => format!("{name}.UTF-8"),
                     })
-                    .collect::<Vec<_>>(),
-                Err(e) => {
-                    log::error!("Failed to run 'locale -a': {e}");
-                    Vec::with_capacity(0)
-                }
-            }
+                })
+                .collect::<Vec<_>>()
         })
+        .collect()
+}
+
+/// Language and territory names translated into the locale itself, with a thread locale
+/// so that the message locale of the greeter is left untouched.
+fn native_names(code: &str, name: &str, territory: &str) -> (Option<String>, Option<String>) {
+    let Ok(locale_name) = CString::new(code) else {
+        return (None, None);
+    };
+    // SAFETY: locale_name is a valid C string, a new locale object is created
+    let locale = unsafe {
+        libc::newlocale(
+            libc::LC_MESSAGES_MASK,
+            locale_name.as_ptr(),
+            std::ptr::null_mut(),
+        )
+    };
+    if locale.is_null() {
+        return (None, None);
+    }
+    // SAFETY: locale is valid, the previous locale of this thread is restored below
+    let previous = unsafe { libc::uselocale(locale) };
+    let native_name = dgettext("iso_639-3", name);
+    let native_territory = (!territory.is_empty()).then(|| dgettext("iso_3166-1", territory));
+    // SAFETY: previous was returned by uselocale, locale is not used afterwards
+    unsafe {
+        libc::uselocale(previous);
+        libc::freelocale(locale);
     }
+    (Some(native_name), native_territory)
+}
+
+/// English language and territory names of LC_IDENTIFICATION,
+/// read from the compiled locale if installed, otherwise from its source
+fn identification(code: &str, installed: bool) -> (Option<String>, Option<String>) {
+    const _NL_IDENTIFICATION_LANGUAGE: libc::nl_item = 786439;
+    const _NL_IDENTIFICATION_TERRITORY: libc::nl_item = 786440;
+
+    if installed && let Ok(name) = CString::new(code) {
+        // SAFETY: name is a valid C string, a new locale object is created
+        let locale = unsafe {
+            libc::newlocale(
+                libc::LC_IDENTIFICATION_MASK,
+                name.as_ptr(),
+                std::ptr::null_mut(),
+            )
+        };
+        if !locale.is_null() {
+            let langinfo = |item| {
+                // SAFETY: locale is valid until freed below, the result is copied
+                let value = unsafe { CStr::from_ptr(libc::nl_langinfo_l(item, locale)) };
+                Some(value.to_string_lossy().into_owned()).filter(|v| !v.is_empty())
+            };
+            let names = (
+                langinfo(_NL_IDENTIFICATION_LANGUAGE),
+                langinfo(_NL_IDENTIFICATION_TERRITORY),
+            );
+            // SAFETY: locale was created by newlocale and is not used afterwards
+            unsafe { libc::freelocale(locale) };
+            return names;
+        }
+    }
+
+    // "de_DE.UTF-8@euro" is defined in "de_DE@euro"
+    let source = match code.split_once('@') {
+        Some((name, modifier)) => format!("{}@{modifier}", name.split('.').next().unwrap()),
+        None => code.split('.').next().unwrap().to_string(),
+    };
+    let Ok(content) = std::fs::read_to_string(Path::new(LOCALE_SOURCES_DIR).join(source)) else {
+        return (None, None);
+    };
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let (k, v) = line.trim().split_once(char::is_whitespace)?;
+            (k == key)
+                .then(|| v.trim().trim_matches('"').to_string())
+                .filter(|v| !v.is_empty())
+        })
+    };
+    (value("language"), value("territory"))
 }
@@ -2,9 +2,34 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
-use serde::Serialize;
-
-use std::sync::OnceLock;
+use gettextrs::dgettext;
+use serde::{Deserialize, Serialize};
+use webkit::{
+    gio::{SocketConnection, UnixSocketAddress, prelude::*},
+    glib,
+};
+
+use std::{cell::RefCell, path::Path, sync::OnceLock};
+
+mod hyprland;
+mod labwc;
+mod niri;
+mod river;
+mod sway;
+mod wayfire;
+mod x11;
+
+// `self::` keeps the hyprland module apart from the hyprland crate
+use super::{
+    dbus::{AccountsService, LocaledManager},
+    state::GreeterState,
+};
+use crate::config::Config;
+
+use self::{
+    hyprland::Hyprland, labwc::Labwc, niri::Niri, river::River, sway::Sway, wayfire::Wayfire,
+    x11::X11,
+};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Layout {
@@ -33,17 +58,130 @@ impl Layout {
     pub fn description(&self) -> &str {
         &self.description
     }
+
+    /// XKB layout and variant, the variant is empty if there is none
+    pub fn layout_variant(&self) -> (&str, &str) {
+        layout_variant(&self.name)
+    }
+
+    /// Copy with the description translated into the message locale of the greeter
+    pub fn localized(&self) -> Layout {
+        static XKEYBOARD_CONFIG: OnceLock<()> = OnceLock::new();
+        XKEYBOARD_CONFIG.get_or_init(|| {
+            if let Err(e) = gettextrs::bindtextdomain("xkeyboard-config", "/usr/share/locale") {
+                log::warn!("Failed to bind xkeyboard-config text domain: {e}");
+            }
+        });
+        Layout {
+            name: self.name.clone(),
+            short_description: self.short_description.clone(),
+            description: dgettext("xkeyboard-config", &self.description),
+        }
+    }
+}
+
+/// Keyboard layout IPC of a compositor or display server
+trait LayoutBackend: Send + Sync {
+    /// Layouts that can be switched to
+    fn layouts(&self) -> Vec<Layout>;
+
+    /// Index of the active layout in layouts
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize>;
+
+    /// Switch to the layout at index of layouts
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool;
+
+    /// Call changed whenever the active layout may have been switched outside of the greeter,
+    /// i.e. with a keybinding of the compositor
+    fn watch(&self, _changed: Box<dyn Fn()>) {}
+
+    /// Turn Num Lock on
+    fn enable_num_lock(&self) -> bool {
+        log::warn!("WebKit Greeter does not support turning Num Lock on here");
+        false
+    }
+}
+
+/// `[layouts]` section of webkit-greeter.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LayoutFilter {
+    /// Layouts offered by the greeter, i.e. ["us", "de@nodeadkeys"], all of them if empty
+    allowed: Vec<String>,
+}
+
+impl LayoutFilter {
+    pub fn allowed(&self) -> &[String] {
+        &self.allowed
+    }
+
+    fn permits(&self, layout: &Layout) -> bool {
+        self.allowed.is_empty() || self.allowed.iter().any(|name| name == layout.name())
+    }
+}
+
+/// Keyboard configuration of the system, set with `localectl set-x11-keymap`
+struct SystemKeymap {
+    /// Layout names, i.e. ["us", "de@nodeadkeys"]
+    layouts: Vec<String>,
+    options: String,
+}
+
+fn system_keymap() -> &'static SystemKeymap {
+    static KEYMAP: OnceLock<SystemKeymap> = OnceLock::new();
+    KEYMAP.get_or_init(|| match LocaledManager::proxy() {
+        Some(proxy) => SystemKeymap {
+            layouts: layout_names(
+                &proxy.x11_layout().unwrap_or_default(),
+                &proxy.x11_variant().unwrap_or_default(),
+            ),
+            options: proxy.x11_options().unwrap_or_default(),
+        },
+        None => {
+            log::warn!("Failed to connect to systemd-localed");
+            SystemKeymap {
+                layouts: vec![],
+                options: String::new(),
+            }
+        }
+    })
 }
 
 pub struct LayoutManager {
+    backend: Box<dyn LayoutBackend>,
+    /// Layouts of the backend, which may switch them by their index
+    available: Vec<Layout>,
+    /// Layouts offered to themes, the ones of the system keymap first
     layouts: Vec<Layout>,
 }
 
 impl LayoutManager {
     pub fn instance() -> &'static LayoutManager {
         static MANAGER: OnceLock<LayoutManager> = OnceLock::new();
-        MANAGER.get_or_init(|| Self {
-            layouts: keyboard_layouts(),
+        MANAGER.get_or_init(|| {
+            let backend = layout_backend();
+            let available = backend.layouts();
+            let filter = Config::instance().layouts();
+            let system = &system_keymap().layouts;
+            let mut layouts: Vec<_> = available
+                .iter()
+                .filter(|l| filter.permits(l))
+                .cloned()
+                .collect();
+            layouts.sort_by_key(|l| {
+                system
+                    .iter()
+                    .position(|name| name == l.name())
+                    .unwrap_or(usize::MAX)
+            });
+            if layouts.is_empty() && !available.is_empty() {
+                log::warn!("None of the allowed keyboard layouts is available");
+            }
+            Self {
+                backend,
+                available,
+                layouts,
+            }
         })
     }
 
@@ -52,90 +190,234 @@ impl LayoutManager {
         &self.layouts
     }
 
-    /// Get current keyboard layouts
-    pub fn layout(&self) -> &Layout {
-        keyboard_layout(&self.layouts)
+    /// Get current keyboard layout, the default one of the system if it is unknown
+    pub fn layout(&self) -> Option<&Layout> {
+        self.backend
+            .active_layout(&self.available)
+            .and_then(|i| self.available.get(i))
+            .or(self.layouts.first())
     }
 
     /// Set keyboard layout
     pub fn set_layout(&self, layout: &str) -> bool {
-        switch_keyboard_layout(layout, &self.layouts)
+        if !self.layouts.iter().any(|l| l.name() == layout) {
+            log::warn!("Keyboard layout {layout} is not available");
+            return false;
+        }
+        match self.available.iter().position(|l| l.name() == layout) {
+            Some(idx) => self.backend.switch_layout(&self.available, idx),
+            None => false,
+        }
     }
-}
 
-fn keyboard_layouts() -> Vec<Layout> {
-    match std::env::var("XDG_SESSION_TYPE").as_deref() {
-        Ok("wayland") => match std::env::var("XDG_CURRENT_DESKTOP").as_deref() {
-            Ok("Hyprland") => hyprland::xkb_layouts(),
-            Ok("sway") => sway::xkb_layouts(),
-            Ok(s) => {
-                log::warn!("WebKit Greeter does not support keyboard layouts of {s} yet");
-                vec![]
-            }
-            Err(e) => {
-                log::error!("Could not get $XDG_CURRENT_DESKTOP environment variable: {e}");
-                vec![]
-            }
-        },
-        Ok("x11") => system_layouts(),
-        _ => {
-            log::error!("Could not get $XDG_SESSION_TYPE environment variable");
-            vec![]
+    /// Turn Num Lock on through the compositor or the X server
+    pub fn enable_num_lock(&self) -> bool {
+        self.backend.enable_num_lock()
+    }
+
+    /// XKB options of the environment or of the system keymap, i.e. "grp:alt_shift_toggle"
+    pub fn xkb_options(&self) -> Option<String> {
+        xkb_options()
+    }
+
+    /// Available layouts preferred by user, taken from the first source that has any of
+    /// AccountsService, the dmrc cached by LightDM and the state of the greeter
+    pub fn user_layouts(&self, username: &str) -> Vec<&Layout> {
+        let state = GreeterState::load();
+        [
+            accounts_layouts(username),
+            dmrc_layouts(username),
+            state.layouts(username).to_vec(),
+        ]
+        .into_iter()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| self.layouts.iter().find(|l| l.name() == name))
+                .collect::<Vec<_>>()
+        })
+        .find(|layouts| !layouts.is_empty())
+        .unwrap_or_default()
+    }
+
+    /// Switch to the preferred layout of user, so that the password is typed with its keymap
+    pub fn select_user(&self, username: &str) -> bool {
+        let Some(layout) = self.user_layouts(username).first().map(|l| l.name()) else {
+            return false;
+        };
+        if self.layout().is_some_and(|l| l.name() == layout) {
+            return true;
         }
+        log::debug!("Switch keyboard layout to {layout} for {username}");
+        self.set_layout(layout)
+    }
+
+    /// Call f with the new layout whenever the active keyboard layout changes
+    pub fn connect_layout_changed<F: Fn(&Layout) + 'static>(&'static self, f: F) {
+        let active = RefCell::new(self.layout().map(|l| l.name().to_string()));
+        self.backend.watch(Box::new(move || {
+            let Some(layout) = self.layout() else {
+                return;
+            };
+            if active.borrow().as_deref() != Some(layout.name()) {
+                log::debug!("Keyboard layout changed to {}", layout.name());
+                active.replace(Some(layout.name().to_string()));
+                f(layout);
+            }
+        }));
     }
 }
 
-fn keyboard_layout(layouts: &[Layout]) -> &Layout {
+/// Connect to an IPC socket of the compositor from the main context
+async fn connect_socket(path: &Path) -> Result<SocketConnection, glib::Error> {
+    webkit::gio::SocketClient::new()
+        .connect_future(&UnixSocketAddress::new(path))
+        .await
+}
+
+fn layout_backend() -> Box<dyn LayoutBackend> {
     match std::env::var("XDG_SESSION_TYPE").as_deref() {
-        Ok("wayland") => match std::env::var("XDG_CURRENT_DESKTOP").as_deref() {
-            Ok("Hyprland") => hyprland::xkb_layout(layouts),
-            Ok("sway") => sway::xkb_layout(layouts),
-            Ok(s) => {
-                log::warn!("WebKit Greeter does not support keyboard layout of {s} yet");
-                layouts.first().unwrap()
+        Ok("wayland") => match std::env::var("XDG_CURRENT_DESKTOP") {
+            Ok(desktop) => {
+                let backend = desktop.split(':').find_map(|name| {
+                    let backend: Box<dyn LayoutBackend> = match name.to_lowercase().as_str() {
+                        "hyprland" => Box::new(Hyprland),
+                        "sway" => Box::new(Sway),
+                        "niri" => Box::new(Niri),
+                        "river" => Box::new(River::new()),
+                        "labwc" => Box::new(Labwc::new()),
+                        "wayfire" => Box::new(Wayfire),
+                        _ => return None,
+                    };
+                    Some(backend)
+                });
+                backend.unwrap_or_else(|| {
+                    log::warn!("WebKit Greeter does not support keyboard layouts of {desktop} yet");
+                    Box::new(Fallback)
+                })
             }
             Err(e) => {
                 log::error!("Could not get $XDG_CURRENT_DESKTOP environment variable: {e}");
-                layouts.first().unwrap()
+                Box::new(Fallback)
             }
         },
-        Ok("x11") => x11::xkb_layout(layouts),
+        Ok("x11") => Box::new(X11),
         _ => {
             log::error!("Could not get $XDG_SESSION_TYPE environment variable");
-            layouts.first().unwrap()
+            Box::new(Fallback)
         }
     }
 }
 
-fn switch_keyboard_layout(layout: &str, layouts: &[Layout]) -> bool {
-    let idx = layouts
+/// Layouts of $XKB_DEFAULT_LAYOUT and $XKB_DEFAULT_VARIANT, which wlroots based compositors
+/// use for their keymap when it is not configured otherwise, or else of the system keymap
+fn default_layouts() -> Vec<Layout> {
+    let env_names = layout_names(
+        &std::env::var("XKB_DEFAULT_LAYOUT").unwrap_or_default(),
+        &std::env::var("XKB_DEFAULT_VARIANT").unwrap_or_default(),
+    );
+    let names = if env_names.is_empty() {
+        &system_keymap().layouts
+    } else {
+        &env_names
+    };
+    let sys_layouts = system_layouts();
+    names
         .iter()
-        .enumerate()
-        .find(|(_, l)| l.name() == layout)
-        .map(|(i, _)| i)
-        .unwrap();
-    match std::env::var("XDG_SESSION_TYPE").as_deref() {
-        Ok("wayland") => match std::env::var("XDG_CURRENT_DESKTOP").as_deref() {
-            Ok("Hyprland") => hyprland::switch_xkb_layout(idx as u8)
-                .inspect_err(|e| log::error!("Failed to set keyboard layout to {layout}: {e}"))
-                .is_ok(),
-            Ok("sway") => hyprland::switch_xkb_layout(idx as u8)
-                .inspect_err(|e| log::error!("Failed to set keyboard layout to {layout}: {e}"))
-                .is_ok(),
-            Ok(s) => {
-                log::warn!("WebKit Greeter does not support switching keyboard layout of {s} yet");
-                false
-            }
-            Err(e) => {
-                log::error!("Could not get $XDG_CURRENT_DESKTOP environment variable: {e}");
-                false
-            }
-        },
-        Ok("x11") => x11::switch_xkb_layout(&layouts[idx]),
-        _ => {
-            log::error!("Could not get $XDG_SESSION_TYPE environment variable");
-            false
-        }
+        .filter_map(|name| sys_layouts.iter().find(|l| l.name() == name).cloned())
+        .collect()
+}
+
+fn xkb_options() -> Option<String> {
+    std::env::var("XKB_DEFAULT_OPTIONS")
+        .ok()
+        .filter(|options| !options.is_empty())
+        .or_else(|| Some(system_keymap().options.clone()))
+        .filter(|options| !options.is_empty())
+}
+
+/// Layout names of comma separated XKB layouts and variants, i.e. "us,de" and ",nodeadkeys"
+fn layout_names(layouts: &str, variants: &str) -> Vec<String> {
+    let mut variants = variants.split(',');
+    layouts
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(
+            |l| match variants.next().map(str::trim).filter(|v| !v.is_empty()) {
+                Some(v) => [l, v].join(LAYOUT_VARIANT_SEP),
+                None => l.to_string(),
+            },
+        )
+        .collect()
+}
+
+/// Layout name of "us\tdvorak" as used by LightDM, or "us(dvorak)" as used by XKB
+fn layout_name(layout: &str) -> String {
+    let layout = layout.trim();
+    if let Some((l, v)) = layout.split_once('\t') {
+        [l, v].join(LAYOUT_VARIANT_SEP)
+    } else if let Some((l, v)) = layout.strip_suffix(')').and_then(|l| l.split_once('(')) {
+        [l, v].join(LAYOUT_VARIANT_SEP)
+    } else {
+        layout.to_string()
+    }
+}
+
+/// XKeyboardLayouts of the display manager extension of AccountsService
+fn accounts_layouts(username: &str) -> Vec<String> {
+    let Some(proxy) = AccountsService::accounts_proxy() else {
+        return vec![];
+    };
+    proxy
+        .find_user_by_name(username)
+        .and_then(|o| AccountsService::display_manager_proxy(o).xkeyboard_layouts())
+        .map(|layouts| layouts.iter().map(|l| layout_name(l)).collect())
+        .unwrap_or_default()
+}
+
+/// `Layout` of the `[Desktop]` group of the dmrc that LightDM caches for user
+fn dmrc_layouts(username: &str) -> Vec<String> {
+    let path = Path::new("/var/cache/lightdm/dmrc").join(format!("{username}.dmrc"));
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .skip_while(|line| line.trim() != "[Desktop]")
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .find_map(|line| line.trim().strip_prefix("Layout="))
+        .map(|layout| vec![layout_name(layout)])
+        .unwrap_or_default()
+}
+
+/// Separator of layout and variant in the name of a layout, i.e. "us@dvorak"
+const LAYOUT_VARIANT_SEP: &str = "@";
+
+/// Layout and variant of the layout name, the variant is empty if there is none
+fn layout_variant(name: &str) -> (&str, &str) {
+    name.split_once(LAYOUT_VARIANT_SEP).unwrap_or((name, ""))
+}
+
+/// Compositors without a supported keyboard layout IPC, the keymap can not be switched
+struct Fallback;
+
+impl LayoutBackend for Fallback {
+    fn layouts(&self) -> Vec<Layout> {
+        default_layouts()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        (!layouts.is_empty()).then_some(0)
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        log::warn!(
+            "WebKit Greeter does not support switching keyboard layout to {} here",
+            layouts[index].name()
+        );
+        false
     }
 }
 
@@ -156,7 +438,7 @@ fn system_layouts() -> Vec<Layout> {
                         v.iter()
                             .map(|v| {
                                 Layout::new(
-                                    [&layout.name, v.name()].join("@"),
+                                    [&layout.name, v.name()].join(LAYOUT_VARIANT_SEP),
                                     layout.short_description.clone(),
                                     layout.description.clone(),
                                 )
@@ -173,184 +455,3 @@ fn system_layouts() -> Vec<Layout> {
         }
     }
 }
-
-mod hyprland {
-    use hyprland::shared::HyprData;
-
-    use super::{Layout, system_layouts};
-
-    pub(super) fn xkb_layouts() -> Vec<Layout> {
-        let sys_layouts = system_layouts();
-        let keyboard = hyprland::data::Devices::get()
-            .expect("Failed to get hyprland devices")
-            .keyboards
-            .into_iter()
-            .find(|kb| kb.main)
-            .expect("No main keyboard found");
-        let layout_names = keyboard.layout;
-        sys_layouts
-            .into_iter()
-            .filter(|l| layout_names.split(',').any(|name| name == l.name()))
-            .collect()
-    }
-
-    pub(super) fn xkb_layout(layouts: &[Layout]) -> &Layout {
-        let active_keymap = hyprland::data::Devices::get()
-            .expect("Failed to get hyprland devices")
-            .keyboards
-            .into_iter()
-            .find(|kb| kb.main)
-            .expect("No main keyboard found")
-            .active_keymap;
-        layouts
-            .iter()
-            .find(|l| l.description() == active_keymap)
-            .unwrap()
-    }
-
-    pub(super) fn switch_xkb_layout(idx: u8) -> Result<(), hyprland::error::HyprError> {
-        hyprland::ctl::switch_xkb_layout::call(
-            "current",
-            hyprland::ctl::switch_xkb_layout::SwitchXKBLayoutCmdTypes::Id(idx),
-        )
-    }
-}
-
-mod sway {
-    use super::{Layout, system_layouts};
-
-    pub(super) fn xkb_layouts() -> Vec<Layout> {
-        let sys_layouts = system_layouts();
-        let keyboard = swayipc::Connection::new()
-            .expect("Failed to connect to sway")
-            .get_inputs()
-            .expect("Failed to get inputs")
-            .into_iter()
-            .find(|input| input.input_type == "keyboard")
-            .expect("No main keyboard found");
-        sys_layouts
-            .into_iter()
-            .filter(|l| {
-                keyboard
-                    .xkb_layout_names
-                    .contains(&l.description().to_string())
-            })
-            .collect()
-    }
-
-    pub(super) fn xkb_layout(layouts: &[Layout]) -> &Layout {
-        let xkb_active_layout_name = swayipc::Connection::new()
-            .expect("Failed to connect to sway")
-            .get_inputs()
-            .expect("Failed to get inputs")
-            .into_iter()
-            .find(|input| input.input_type == "keyboard")
-            .expect("No main keyboard found")
-            .xkb_active_layout_name
-            .unwrap();
-        layouts
-            .iter()
-            .find(|l| l.description() == xkb_active_layout_name)
-            .unwrap()
-    }
-
-    pub(super) fn switch_xkb_layout(idx: u8) -> Result<(), swayipc::Error> {
-        swayipc::Connection::new()
-            .expect("Failed to connect to sway")
-            .run_command(format!("input type:keyboard xkb_switch_layout {idx}"))
-            .expect("Failed to run sway command")
-            .into_iter()
-            .next()
-            .unwrap()
-    }
-}
-
-mod x11 {
-    use x11rb::{
-        connection::Connection,
-        protocol::xproto::{AtomEnum, ConnectionExt, PropMode, change_property, get_property},
-    };
-
-    use super::Layout;
-
-    const LAYOUT_VARIANT_SEP: &str = "@";
-
-    fn xkb_rmlvo() -> Vec<String> {
-        if let Ok((conn, screen_id)) = x11rb::connect(None)
-            && let Ok(request) = conn.intern_atom(false, b"_XKB_RULES_NAMES")
-            && let Ok(atom_reply) = request.reply()
-        {
-            let reply = get_property(
-                &conn,
-                false,
-                conn.setup().roots[screen_id].root,
-                atom_reply.atom,
-                AtomEnum::STRING,
-                0,
-                1024,
-            )
-            .unwrap()
-            .reply()
-            .unwrap();
-            let prop = String::from_utf8_lossy(&reply.value);
-            let rmlvo: Vec<_> = prop.split_terminator('\0').map(str::to_string).collect();
-            if rmlvo.len() == 5 {
-                return rmlvo;
-            } else {
-                log::error!("Target window property is not an valid rmlvo: {rmlvo:?}");
-            }
-        }
-
-        log::error!("Failed to get root_window keyboard layout");
-        vec!["".to_string(); 5]
-    }
-
-    pub(super) fn xkb_layout(layouts: &[Layout]) -> &Layout {
-        if let Some(lv) = xkb_rmlvo().get(2..4) {
-            let layout = if lv.iter().any(|s| s.is_empty()) {
-                lv.first().unwrap().to_string()
-            } else {
-                lv.join(LAYOUT_VARIANT_SEP)
-            };
-            if let Some(l) = layouts.iter().find(|l| l.name == layout) {
-                return l;
-            };
-        }
-
-        layouts.first().unwrap()
-    }
-
-    pub(super) fn switch_xkb_layout(layout: &Layout) -> bool {
-        let old = xkb_rmlvo();
-        let rmlvo = if old.iter().all(String::is_empty) {
-            log::error!("old keyboard layout is not an valid rmlvo: {old:?}");
-            return false;
-        } else {
-            let lv: Vec<_> = layout.name().split(LAYOUT_VARIANT_SEP).collect();
-            let (l, v) = if lv.len() == 2 {
-                (lv[0], lv[1])
-            } else {
-                (lv[0], "")
-            };
-            [&old[0], &old[1], l, v, &old[4], ""].join("\0")
-        };
-        if let Ok((conn, screen_id)) = x11rb::connect(None)
-            && let Ok(request) = conn.intern_atom(false, b"_XKB_RULES_NAMES")
-            && let Ok(atom_reply) = request.reply()
-        {
-            change_property(
-                &conn,
-                PropMode::REPLACE,
-                conn.setup().roots[screen_id].root,
-                atom_reply.atom,
-                AtomEnum::STRING,
-                8,
-                rmlvo.len() as _,
-                rmlvo.as_bytes(),
-            )
-            .is_ok_and(|reply| reply.check().is_ok())
-        } else {
-            false
-        }
-    }
-}
@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use hyprland::{data::Keyboard, shared::HyprData};
+use webkit::{
+    gio::{DataInputStream, prelude::*},
+    glib::{self, MainContext, Priority},
+};
+
+use std::path::PathBuf;
+
+use super::{LAYOUT_VARIANT_SEP, Layout, LayoutBackend, connect_socket, system_layouts};
+
+/// Keyboard layouts of the main keyboard through the Hyprland IPC
+pub(super) struct Hyprland;
+
+impl LayoutBackend for Hyprland {
+    fn layouts(&self) -> Vec<Layout> {
+        let Some(keyboard) = main_keyboard() else {
+            return vec![];
+        };
+        let sys_layouts = system_layouts();
+        let mut variants = keyboard.variant.split(',');
+        // one layout for each group of the keymap keeps the indices of switchxkblayout,
+        // layouts unknown to xkeyboard-config are named as in Hyprland
+        keyboard
+            .layout
+            .split(',')
+            .map(|layout| match variants.next().filter(|v| !v.is_empty()) {
+                Some(variant) => [layout, variant].join(LAYOUT_VARIANT_SEP),
+                None => layout.to_string(),
+            })
+            .map(|name| {
+                sys_layouts
+                    .iter()
+                    .find(|l| l.name() == name)
+                    .cloned()
+                    .unwrap_or_else(|| Layout::new(name.clone(), None, name))
+            })
+            .collect()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let active_keymap = main_keyboard()?.active_keymap;
+        layouts
+            .iter()
+            .position(|l| l.description() == active_keymap)
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        hyprland::ctl::switch_xkb_layout::call(
+            "current",
+            hyprland::ctl::switch_xkb_layout::SwitchXKBLayoutCmdTypes::Id(index as u8),
+        )
+        .inspect_err(|e| {
+            log::error!(
+                "Failed to set keyboard layout to {}: {e}",
+                layouts[index].name()
+            )
+        })
+        .is_ok()
+    }
+
+    fn enable_num_lock(&self) -> bool {
+        hyprland::keyword::Keyword::set("input:numlock_by_default", "true")
+            .inspect_err(|e| log::error!("Failed to turn Num Lock on: {e}"))
+            .is_ok()
+    }
+
+    fn watch(&self, changed: Box<dyn Fn()>) {
+        MainContext::default().spawn_local(async move {
+            if let Err(e) = watch_events(changed).await {
+                log::error!("Failed to watch hyprland events: {e}");
+            }
+        });
+    }
+}
+
+/// Read `activelayout>>KEYBOARD,LAYOUT` events of the event socket of Hyprland
+async fn watch_events(changed: Box<dyn Fn()>) -> Result<(), glib::Error> {
+    let Ok(signature) = std::env::var("HYPRLAND_INSTANCE_SIGNATURE") else {
+        log::error!("Could not get $HYPRLAND_INSTANCE_SIGNATURE environment variable");
+        return Ok(());
+    };
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("hypr"))
+        .ok()
+        .filter(|dir| dir.is_dir())
+        .unwrap_or(PathBuf::from("/tmp/hypr"));
+    let path = runtime_dir.join(signature).join(".socket2.sock");
+
+    let conn = connect_socket(&path).await?;
+    let events = DataInputStream::new(&conn.input_stream());
+    while let Some(line) = events.read_line_utf8_future(Priority::DEFAULT).await? {
+        if line.starts_with("activelayout>>") {
+            changed();
+        }
+    }
+    Ok(())
+}
+
+fn main_keyboard() -> Option<Keyboard> {
+    match hyprland::data::Devices::get() {
+        Ok(devices) => {
+            let keyboard = devices.keyboards.into_iter().find(|kb| kb.main);
+            if keyboard.is_none() {
+                log::error!("No main keyboard found");
+            }
+            keyboard
+        }
+        Err(e) => {
+            log::error!("Failed to get hyprland devices: {e}");
+            None
+        }
+    }
+}
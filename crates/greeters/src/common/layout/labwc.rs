@@ -0,0 +1,86 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use webkit::glib::user_config_dir;
+
+use std::{process::Command, sync::Mutex};
+
+use super::{Layout, LayoutBackend, default_layouts, layout_variant, system_layouts};
+
+/// labwc takes its keymap from XKB_DEFAULT_* of its environment file and has no layout IPC,
+/// so layouts are switched by rewriting that file of the greeter user and reconfiguring labwc.
+pub(super) struct Labwc {
+    active: Mutex<Option<String>>,
+}
+
+impl Labwc {
+    pub(super) fn new() -> Self {
+        let active = default_layouts().first().map(|l| l.name().to_string());
+        Self {
+            active: Mutex::new(active),
+        }
+    }
+}
+
+impl LayoutBackend for Labwc {
+    fn layouts(&self) -> Vec<Layout> {
+        system_layouts()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let active = self.active.lock().unwrap();
+        layouts
+            .iter()
+            .position(|l| active.as_deref() == Some(l.name()))
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        let name = layouts[index].name();
+        let result = write_environment(name).and_then(|_| {
+            let status = Command::new("labwc")
+                .arg("--reconfigure")
+                .status()
+                .map_err(|e| e.to_string())?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(format!("labwc --reconfigure {status}"))
+            }
+        });
+        match result {
+            Ok(()) => {
+                *self.active.lock().unwrap() = Some(name.to_string());
+                true
+            }
+            Err(e) => {
+                log::error!("Failed to set keyboard layout to {name}: {e}");
+                false
+            }
+        }
+    }
+}
+
+/// Replace XKB_DEFAULT_LAYOUT and XKB_DEFAULT_VARIANT in the environment file of labwc
+fn write_environment(name: &str) -> Result<(), String> {
+    let path = user_config_dir().join("labwc").join("environment");
+    let (layout, variant) = layout_variant(name);
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    let mut lines: Vec<_> = content
+        .lines()
+        .filter(|line| {
+            let key = line.split('=').next().unwrap_or_default().trim();
+            key != "XKB_DEFAULT_LAYOUT" && key != "XKB_DEFAULT_VARIANT"
+        })
+        .map(str::to_string)
+        .collect();
+    lines.push(format!("XKB_DEFAULT_LAYOUT={layout}"));
+    if !variant.is_empty() {
+        lines.push(format!("XKB_DEFAULT_VARIANT={variant}"));
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())
+}
@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde_json::{Value, json};
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+};
+
+use super::{Layout, LayoutBackend, system_layouts};
+
+/// Keyboard layouts through the JSON socket of niri at $NIRI_SOCKET
+pub(super) struct Niri;
+
+impl LayoutBackend for Niri {
+    fn layouts(&self) -> Vec<Layout> {
+        let Some(names) = keyboard_layouts().and_then(|kl| kl.get("names").cloned()) else {
+            return vec![];
+        };
+        let names: Vec<String> = serde_json::from_value(names).unwrap_or_default();
+        let sys_layouts = system_layouts();
+        // niri names layouts by description, one layout for each of its names keeps the indices
+        // of current_idx and SwitchLayout, layouts unknown to xkeyboard-config are named as in niri
+        names
+            .into_iter()
+            .map(|name| {
+                sys_layouts
+                    .iter()
+                    .find(|l| l.description() == name)
+                    .cloned()
+                    .unwrap_or_else(|| Layout::new(name.clone(), None, name))
+            })
+            .collect()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let current_idx = keyboard_layouts()?.get("current_idx")?.as_u64()? as usize;
+        (current_idx < layouts.len()).then_some(current_idx)
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        let action = json!({"Action": {"SwitchLayout": {"layout": {"Index": index}}}});
+        match request(&action) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!(
+                    "Failed to set keyboard layout to {}: {e}",
+                    layouts[index].name()
+                );
+                false
+            }
+        }
+    }
+}
+
+/// `{"names": [...], "current_idx": n}` of the KeyboardLayouts request
+fn keyboard_layouts() -> Option<Value> {
+    match request(&json!("KeyboardLayouts")) {
+        Ok(mut reply) => reply.get_mut("KeyboardLayouts").map(Value::take),
+        Err(e) => {
+            log::error!("Failed to get niri keyboard layouts: {e}");
+            None
+        }
+    }
+}
+
+/// Send a request and return the `Ok` value of the reply
+fn request(request: &Value) -> Result<Value, String> {
+    let path = std::env::var("NIRI_SOCKET").map_err(|e| format!("$NIRI_SOCKET: {e}"))?;
+    let mut stream = UnixStream::connect(path).map_err(|e| e.to_string())?;
+    writeln!(stream, "{request}").map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    let mut reply: Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+    match reply.get_mut("Ok") {
+        Some(ok) => Ok(ok.take()),
+        None => Err(reply
+            .get("Err")
+            .and_then(Value::as_str)
+            .unwrap_or("invalid reply")
+            .to_string()),
+    }
+}
@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use std::{process::Command, sync::Mutex};
+
+use super::{Layout, LayoutBackend, default_layouts, layout_variant, system_layouts, xkb_options};
+
+/// river sets a single keymap with `riverctl keyboard-layout` and can not be queried,
+/// so the active layout is remembered by the greeter.
+pub(super) struct River {
+    active: Mutex<Option<String>>,
+}
+
+impl River {
+    pub(super) fn new() -> Self {
+        let active = default_layouts().first().map(|l| l.name().to_string());
+        Self {
+            active: Mutex::new(active),
+        }
+    }
+}
+
+impl LayoutBackend for River {
+    fn layouts(&self) -> Vec<Layout> {
+        system_layouts()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let active = self.active.lock().unwrap();
+        layouts
+            .iter()
+            .position(|l| active.as_deref() == Some(l.name()))
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        let name = layouts[index].name();
+        let (layout, variant) = layout_variant(name);
+        let mut command = Command::new("riverctl");
+        command.arg("keyboard-layout");
+        if !variant.is_empty() {
+            command.args(["-variant", variant]);
+        }
+        if let Some(options) = xkb_options() {
+            command.args(["-options", &options]);
+        }
+        match command.arg(layout).status() {
+            Ok(status) if status.success() => {
+                *self.active.lock().unwrap() = Some(name.to_string());
+                true
+            }
+            Ok(status) => {
+                log::error!("Failed to set keyboard layout to {name}: riverctl {status}");
+                false
+            }
+            Err(e) => {
+                log::error!("Failed to set keyboard layout to {name}: {e}");
+                false
+            }
+        }
+    }
+}
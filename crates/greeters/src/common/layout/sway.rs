@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use swayipc::{Connection, Input};
+use webkit::{
+    gio::prelude::*,
+    glib::{self, MainContext, Priority},
+};
+
+use std::path::Path;
+
+use super::{Layout, LayoutBackend, connect_socket, system_layouts};
+
+const IPC_MAGIC: &[u8] = b"i3-ipc";
+const IPC_HEADER_LEN: usize = 14;
+const SUBSCRIBE: u32 = 2;
+const INPUT_EVENT: u32 = 0x80000015;
+
+/// Keyboard layouts of the first keyboard through the sway IPC
+pub(super) struct Sway;
+
+impl LayoutBackend for Sway {
+    fn layouts(&self) -> Vec<Layout> {
+        let Some(keyboard) = keyboard() else {
+            return vec![];
+        };
+        let sys_layouts = system_layouts();
+        // sway names layouts by description, one layout for each of its names keeps the indices
+        // of xkb_active_layout_index and xkb_switch_layout, variants share the description of
+        // their layout so the layout itself is found first
+        keyboard
+            .xkb_layout_names
+            .into_iter()
+            .map(|name| {
+                sys_layouts
+                    .iter()
+                    .find(|l| l.description() == name)
+                    .cloned()
+                    .unwrap_or_else(|| Layout::new(name.clone(), None, name))
+            })
+            .collect()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let index = usize::try_from(keyboard()?.xkb_active_layout_index?).ok()?;
+        (index < layouts.len()).then_some(index)
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        run_command(&format!("input type:keyboard xkb_switch_layout {index}"))
+            .inspect_err(|e| {
+                log::error!(
+                    "Failed to set keyboard layout to {}: {e}",
+                    layouts[index].name()
+                )
+            })
+            .is_ok()
+    }
+
+    fn enable_num_lock(&self) -> bool {
+        run_command("input type:keyboard xkb_numlock enabled")
+            .inspect_err(|e| log::error!("Failed to turn Num Lock on: {e}"))
+            .is_ok()
+    }
+
+    fn watch(&self, changed: Box<dyn Fn()>) {
+        MainContext::default().spawn_local(async move {
+            if let Err(e) = watch_inputs(changed).await {
+                log::error!("Failed to watch sway inputs: {e}");
+            }
+        });
+    }
+}
+
+/// Subscribe to input events of sway, layouts are switched by `xkb_layout` changes
+async fn watch_inputs(changed: Box<dyn Fn()>) -> Result<(), glib::Error> {
+    let Ok(path) = std::env::var("SWAYSOCK") else {
+        log::error!("Could not get $SWAYSOCK environment variable");
+        return Ok(());
+    };
+    let conn = connect_socket(Path::new(&path)).await?;
+
+    let payload = br#"["input"]"#;
+    let mut message = IPC_MAGIC.to_vec();
+    message.extend((payload.len() as u32).to_ne_bytes());
+    message.extend(SUBSCRIBE.to_ne_bytes());
+    message.extend(payload);
+    conn.output_stream()
+        .write_all_future(message, Priority::DEFAULT)
+        .await
+        .map_err(|(_, e)| e)?;
+
+    let input = conn.input_stream();
+    loop {
+        let (header, n, _) = input
+            .read_all_future(vec![0; IPC_HEADER_LEN], Priority::DEFAULT)
+            .await
+            .map_err(|(_, e)| e)?;
+        if n < IPC_HEADER_LEN || !header.starts_with(IPC_MAGIC) {
+            return Ok(());
+        }
+        let len = u32::from_ne_bytes(header[6..10].try_into().unwrap()) as usize;
+        let type_ = u32::from_ne_bytes(header[10..14].try_into().unwrap());
+        let (payload, n, _) = input
+            .read_all_future(vec![0; len], Priority::DEFAULT)
+            .await
+            .map_err(|(_, e)| e)?;
+        if n < len {
+            return Ok(());
+        }
+        if type_ == INPUT_EVENT
+            && let Ok(event) = serde_json::from_slice::<serde_json::Value>(&payload)
+            && event["change"] == "xkb_layout"
+        {
+            changed();
+        }
+    }
+}
+
+fn run_command(command: &str) -> Result<(), swayipc::Error> {
+    Connection::new()?
+        .run_command(command)?
+        .into_iter()
+        .collect()
+}
+
+fn keyboard() -> Option<Input> {
+    match Connection::new().and_then(|mut conn| conn.get_inputs()) {
+        Ok(inputs) => {
+            let keyboard = inputs
+                .into_iter()
+                .find(|input| input.input_type == "keyboard");
+            if keyboard.is_none() {
+                log::error!("No keyboard found");
+            }
+            keyboard
+        }
+        Err(e) => {
+            log::error!("Failed to get sway inputs: {e}");
+            None
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde_json::{Value, json};
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+};
+
+use super::{Layout, LayoutBackend, layout_variant, system_layouts};
+
+/// Keyboard layouts through the IPC socket of wayfire at $WAYFIRE_SOCKET, which needs its IPC
+/// plugins enabled. The keymap is switched by setting the `input/xkb_layout` and
+/// `input/xkb_variant` options to a single layout.
+pub(super) struct Wayfire;
+
+impl LayoutBackend for Wayfire {
+    fn layouts(&self) -> Vec<Layout> {
+        system_layouts()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let layout = config_option("input/xkb_layout")?;
+        let variant = config_option("input/xkb_variant").unwrap_or_default();
+        let layout = layout.split(',').next()?.trim();
+        let variant = variant.split(',').next().unwrap_or_default().trim();
+        layouts
+            .iter()
+            .position(|l| layout_variant(l.name()) == (layout, variant))
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        let name = layouts[index].name();
+        let (layout, variant) = layout_variant(name);
+        let data = json!({"input/xkb_layout": layout, "input/xkb_variant": variant});
+        match request("wayfire/set-config-options", data) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to set keyboard layout to {name}: {e}");
+                false
+            }
+        }
+    }
+
+    fn enable_num_lock(&self) -> bool {
+        let data = json!({"input/kb_numlock_default_state": true});
+        match request("wayfire/set-config-options", data) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to turn Num Lock on: {e}");
+                false
+            }
+        }
+    }
+}
+
+fn config_option(option: &str) -> Option<String> {
+    match request("wayfire/get-config-option", json!({ "option": option })) {
+        Ok(reply) => reply
+            .get("value")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        Err(e) => {
+            log::error!("Failed to get wayfire option {option}: {e}");
+            None
+        }
+    }
+}
+
+/// Messages of the wayfire IPC are JSON prefixed with their length as 32-bit little endian
+fn request(method: &str, data: Value) -> Result<Value, String> {
+    let path = std::env::var("WAYFIRE_SOCKET").map_err(|e| format!("$WAYFIRE_SOCKET: {e}"))?;
+    let mut stream = UnixStream::connect(path).map_err(|e| e.to_string())?;
+    let message = json!({ "method": method, "data": data }).to_string();
+    stream
+        .write_all(&(message.len() as u32).to_le_bytes())
+        .and_then(|_| stream.write_all(message.as_bytes()))
+        .map_err(|e| e.to_string())?;
+
+    let mut len = [0; 4];
+    stream.read_exact(&mut len).map_err(|e| e.to_string())?;
+    let mut reply = vec![0; u32::from_le_bytes(len) as usize];
+    stream.read_exact(&mut reply).map_err(|e| e.to_string())?;
+    let reply: Value = serde_json::from_slice(&reply).map_err(|e| e.to_string())?;
+    match reply.get("error") {
+        Some(error) => Err(error.as_str().unwrap_or("invalid reply").to_string()),
+        None => Ok(reply),
+    }
+}
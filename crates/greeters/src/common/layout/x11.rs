@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use webkit::glib::{self, ControlFlow, IOCondition};
+use x11rb::{
+    connection::Connection,
+    protocol::{
+        Event,
+        xkb::{ConnectionExt as _, EventType, Group, ID, MapPart, SelectEventsAux, StatePart},
+        xproto::{
+            AtomEnum, ChangeWindowAttributesAux, ConnectionExt, EventMask, ModMask, PropMode,
+            change_property, get_property,
+        },
+    },
+};
+
+use std::os::fd::AsRawFd;
+
+use super::{LAYOUT_VARIANT_SEP, Layout, LayoutBackend, layout_variant, system_layouts};
+
+/// Keyboard layouts of the X server, switched through the `_XKB_RULES_NAMES` root window property
+pub(super) struct X11;
+
+impl LayoutBackend for X11 {
+    fn layouts(&self) -> Vec<Layout> {
+        system_layouts()
+    }
+
+    fn active_layout(&self, layouts: &[Layout]) -> Option<usize> {
+        let rmlvo = xkb_rmlvo();
+        // With several layouts, i.e. "us,de", the XKB group is the index of the active one
+        let group = xkb_group().unwrap_or_default();
+        let layout = rmlvo[2].split(',').nth(group)?;
+        let variant = rmlvo[3].split(',').nth(group).unwrap_or_default();
+        let layout = if variant.is_empty() {
+            layout.to_string()
+        } else {
+            [layout, variant].join(LAYOUT_VARIANT_SEP)
+        };
+        layouts.iter().position(|l| l.name() == layout)
+    }
+
+    fn switch_layout(&self, layouts: &[Layout], index: usize) -> bool {
+        switch_xkb_layout(&layouts[index])
+    }
+
+    /// Lock Mod2, the modifier Num Lock is mapped to by the usual keymaps
+    fn enable_num_lock(&self) -> bool {
+        let locked = x11rb::connect(None).is_ok_and(|(conn, _)| {
+            use_xkb(&conn)
+                && conn
+                    .xkb_latch_lock_state(
+                        ID::USE_CORE_KBD.into(),
+                        ModMask::M2,
+                        ModMask::M2,
+                        false,
+                        Group::M1,
+                        ModMask::from(0u16),
+                        false,
+                        0,
+                    )
+                    .is_ok_and(|cookie| cookie.check().is_ok())
+        });
+        if !locked {
+            log::error!("Failed to turn Num Lock on");
+        }
+        locked
+    }
+
+    fn watch(&self, changed: Box<dyn Fn()>) {
+        let conn = match x11rb::connect(None) {
+            Ok((conn, screen_id)) => {
+                let root = conn.setup().roots[screen_id].root;
+                let selected = use_xkb(&conn)
+                    && conn
+                        .xkb_select_events(
+                            ID::USE_CORE_KBD.into(),
+                            EventType::from(0u16),
+                            EventType::STATE_NOTIFY,
+                            MapPart::from(0u16),
+                            MapPart::from(0u16),
+                            &SelectEventsAux::new(),
+                        )
+                        .is_ok_and(|cookie| cookie.check().is_ok())
+                    // _XKB_RULES_NAMES is replaced, i.e. by setxkbmap
+                    && conn
+                        .change_window_attributes(
+                            root,
+                            &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+                        )
+                        .is_ok_and(|cookie| cookie.check().is_ok());
+                if !selected {
+                    log::error!("Failed to select XKB state events");
+                    return;
+                }
+                conn
+            }
+            Err(e) => {
+                log::error!("Failed to connect to X server: {e}");
+                return;
+            }
+        };
+
+        let fd = conn.stream().as_raw_fd();
+        glib::unix_fd_add_local(fd, IOCondition::IN, move |_, _| {
+            let mut layout_changed = false;
+            loop {
+                match conn.poll_for_event() {
+                    Ok(Some(Event::XkbStateNotify(event))) => {
+                        layout_changed |= event.changed.contains(StatePart::GROUP_STATE);
+                    }
+                    Ok(Some(Event::PropertyNotify(_))) => layout_changed = true,
+                    Ok(Some(_)) => {}
+                    Ok(None) => break,
+                    Err(e) => {
+                        log::error!("Lost connection to X server: {e}");
+                        return ControlFlow::Break;
+                    }
+                }
+            }
+            if layout_changed {
+                changed();
+            }
+            ControlFlow::Continue
+        });
+    }
+}
+
+/// Enable the XKB extension on the connection
+fn use_xkb(conn: &impl Connection) -> bool {
+    conn.xkb_use_extension(1, 0)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .is_some_and(|reply| reply.supported)
+}
+
+/// Index of the active XKB group of the core keyboard
+fn xkb_group() -> Option<usize> {
+    let (conn, _) = x11rb::connect(None).ok()?;
+    if !use_xkb(&conn) {
+        return None;
+    }
+    let state = conn
+        .xkb_get_state(ID::USE_CORE_KBD.into())
+        .ok()?
+        .reply()
+        .ok()?;
+    Some(u8::from(state.group) as usize)
+}
+
+fn xkb_rmlvo() -> Vec<String> {
+    if let Ok((conn, screen_id)) = x11rb::connect(None)
+        && let Ok(request) = conn.intern_atom(false, b"_XKB_RULES_NAMES")
+        && let Ok(atom_reply) = request.reply()
+    {
+        let reply = get_property(
+            &conn,
+            false,
+            conn.setup().roots[screen_id].root,
+            atom_reply.atom,
+            AtomEnum::STRING,
+            0,
+            1024,
+        )
+        .unwrap()
+        .reply()
+        .unwrap();
+        let prop = String::from_utf8_lossy(&reply.value);
+        let rmlvo: Vec<_> = prop.split_terminator('\0').map(str::to_string).collect();
+        if rmlvo.len() == 5 {
+            return rmlvo;
+        } else {
+            log::error!("Target window property is not an valid rmlvo: {rmlvo:?}");
+        }
+    }
+
+    log::error!("Failed to get root_window keyboard layout");
+    vec!["".to_string(); 5]
+}
+
+fn switch_xkb_layout(layout: &Layout) -> bool {
+    let old = xkb_rmlvo();
+    let rmlvo = if old.iter().all(String::is_empty) {
+        log::error!("old keyboard layout is not an valid rmlvo: {old:?}");
+        return false;
+    } else {
+        let (l, v) = layout_variant(layout.name());
+        [&old[0], &old[1], l, v, &old[4], ""].join("\0")
+    };
+    if let Ok((conn, screen_id)) = x11rb::connect(None)
+        && let Ok(request) = conn.intern_atom(false, b"_XKB_RULES_NAMES")
+        && let Ok(atom_reply) = request.reply()
+    {
+        change_property(
+            &conn,
+            PropMode::REPLACE,
+            conn.setup().roots[screen_id].root,
+            atom_reply.atom,
+            AtomEnum::STRING,
+            8,
+            rmlvo.len() as _,
+            rmlvo.as_bytes(),
+        )
+        .is_ok_and(|reply| reply.check().is_ok())
+    } else {
+        false
+    }
+}
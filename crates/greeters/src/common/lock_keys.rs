@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use gtk::{gdk, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use std::rc::Rc;
+
+/// `[keyboard]` section of webkit-greeter.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct KeyboardSettings {
+    /// Turn Num Lock on when the greeter starts
+    numlock: bool,
+}
+
+impl KeyboardSettings {
+    pub fn numlock(&self) -> bool {
+        self.numlock
+    }
+}
+
+/// State of the lock keys of the keyboard, as seen by GDK
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LockKeys {
+    caps_lock: bool,
+    num_lock: bool,
+}
+
+impl LockKeys {
+    fn keyboard() -> Option<gdk::Device> {
+        gdk::Display::default()?.default_seat()?.keyboard()
+    }
+
+    pub fn current() -> Self {
+        match Self::keyboard() {
+            Some(keyboard) => Self {
+                caps_lock: keyboard.caps_lock_state(),
+                num_lock: keyboard.num_lock_state(),
+            },
+            None => Self::default(),
+        }
+    }
+
+    pub fn caps_lock(&self) -> bool {
+        self.caps_lock
+    }
+
+    pub fn num_lock(&self) -> bool {
+        self.num_lock
+    }
+
+    /// Call f with the new state whenever Caps Lock or Num Lock is toggled
+    pub fn connect_changed<F: Fn(LockKeys) + 'static>(f: F) {
+        let Some(keyboard) = Self::keyboard() else {
+            log::warn!("No keyboard found to watch lock keys");
+            return;
+        };
+        let f = Rc::new(f);
+        keyboard.connect_caps_lock_state_notify({
+            let f = f.clone();
+            move |_| f(Self::current())
+        });
+        keyboard.connect_num_lock_state_notify(move |_| f(Self::current()));
+    }
+}
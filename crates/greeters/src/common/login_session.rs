@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde::Serialize;
+
+use super::dbus::{LogindManager, SessionExt};
+
+/// A session that is already running, managed by systemd-logind
+#[derive(Debug, Clone, Serialize)]
+pub struct LoginSession {
+    id: String,
+    username: String,
+    uid: u32,
+    seat: String,
+    vt: Option<u32>,
+    tty: String,
+    #[serde(rename(serialize = "type"))]
+    type_: String,
+    active: bool,
+    idle: bool,
+    /// Idle since timestamp in microseconds (CLOCK_REALTIME), 0 if not idle
+    idle_since: u64,
+}
+
+impl LoginSession {
+    fn new(session: SessionExt) -> Self {
+        let proxy = LogindManager::session_proxy(session.object_path.clone())
+            .inspect_err(|e| log::error!("Failed to get session {}: {e}", session.session_id))
+            .ok();
+        let vt = proxy
+            .as_ref()
+            .and_then(|p| p.vtnr().ok())
+            .filter(|vt| *vt > 0);
+        let type_ = proxy
+            .as_ref()
+            .and_then(|p| p.type_().ok())
+            .unwrap_or_default();
+        let active = proxy.as_ref().is_some_and(|p| p.active().unwrap_or(false));
+        Self {
+            id: session.session_id,
+            username: session.user_name,
+            uid: session.uid,
+            seat: session.seat_id,
+            vt,
+            tty: session.tty,
+            type_,
+            active,
+            idle: session.idle,
+            idle_since: session.idle_timestamp,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub fn seat(&self) -> &str {
+        &self.seat
+    }
+
+    pub fn vt(&self) -> Option<u32> {
+        self.vt
+    }
+
+    pub fn tty(&self) -> &str {
+        &self.tty
+    }
+
+    pub fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn idle(&self) -> bool {
+        self.idle
+    }
+
+    fn is_graphical(&self) -> bool {
+        matches!(self.type_.as_str(), "x11" | "wayland" | "mir")
+    }
+}
+
+pub struct SessionsManager;
+impl SessionsManager {
+    /// Graphical user sessions that are currently running
+    pub fn sessions() -> Vec<LoginSession> {
+        match LogindManager::proxy().list_sessions_ex() {
+            Ok(sessions) => sessions
+                .into_iter()
+                .filter(|s| s.class == "user")
+                .map(LoginSession::new)
+                .filter(LoginSession::is_graphical)
+                .collect(),
+            Err(e) => {
+                log::error!("Failed to list logind sessions: {e}");
+                vec![]
+            }
+        }
+    }
+
+    /// Running graphical session of the user, preferring the one on the greeter's seat
+    pub fn session(username: &str) -> Option<LoginSession> {
+        let seat = std::env::var("XDG_SEAT").unwrap_or("seat0".to_string());
+        let mut sessions: Vec<_> = Self::sessions()
+            .into_iter()
+            .filter(|s| s.username() == username)
+            .collect();
+        sessions.sort_by_key(|s| s.seat() != seat);
+        sessions.into_iter().next()
+    }
+
+    /// Switch to the running graphical session of the user
+    pub fn activate_session(username: &str) -> zbus::Result<()> {
+        match Self::session(username) {
+            Some(session) => LogindManager::proxy().activate_session(session.id()),
+            None => Err(zbus::Error::Failure(format!(
+                "{username} has no running graphical session"
+            ))),
+        }
+    }
+}
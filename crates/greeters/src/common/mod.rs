@@ -6,14 +6,26 @@
 #![allow(unused_imports)]
 
 mod accounts;
+mod avatar;
 mod dbus;
 mod language;
 mod layout;
+mod lock_keys;
+mod login_session;
 mod power;
+mod screensaver;
 mod session;
+mod state;
 
-pub use accounts::{User, UserManager};
-pub use language::{Language, LanguageManager};
-pub use layout::{Layout, LayoutManager};
-pub use power::PowerManager;
-pub use session::{Session, SessionManager};
+pub use accounts::{
+    AccountStatus, AccountType, LoginRecord, PasswordMode, User, UserFilter, UserManager,
+};
+pub use avatar::{AVATAR_URI_PREFIX, Avatar};
+pub use language::{Language, LanguageManager, LanguageOrder, LanguageSort};
+pub use layout::{Layout, LayoutFilter, LayoutManager};
+pub use lock_keys::{KeyboardSettings, LockKeys};
+pub use login_session::{LoginSession, SessionsManager};
+pub use power::{ActionPolicy, PowerAction, PowerManager, PowerPolicy};
+pub use screensaver::Screensaver;
+pub use session::{CustomSession, Session, SessionManager};
+pub use state::GreeterState;
@@ -2,8 +2,117 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
+use serde::{Deserialize, Serialize};
+use webkit::glib::DateTime;
+
 use super::dbus::LogindManager;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerAction {
+    Hibernate,
+    Restart,
+    Shutdown,
+    Suspend,
+}
+
+impl PowerAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hibernate => "hibernate",
+            Self::Restart => "restart",
+            Self::Shutdown => "shutdown",
+            Self::Suspend => "suspend",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionPolicy {
+    /// The action can be triggered by themes
+    #[default]
+    Allow,
+    /// The action has to be confirmed in a greeter dialog before it runs
+    Confirm,
+    /// The action can never be triggered from the greeter
+    Deny,
+}
+
+/// `[power]` section of webkit-greeter.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PowerPolicy {
+    hibernate: ActionPolicy,
+    restart: ActionPolicy,
+    shutdown: ActionPolicy,
+    suspend: ActionPolicy,
+    /// Local time ranges in which power actions are permitted, i.e. ["08:00-18:00"].
+    /// Power actions are permitted at any time if empty.
+    allowed_hours: Vec<String>,
+}
+
+impl PowerPolicy {
+    pub fn policy(&self, action: PowerAction) -> ActionPolicy {
+        match action {
+            PowerAction::Hibernate => self.hibernate,
+            PowerAction::Restart => self.restart,
+            PowerAction::Shutdown => self.shutdown,
+            PowerAction::Suspend => self.suspend,
+        }
+    }
+
+    /// Whether the action is permitted by the policy right now
+    pub fn permits(&self, action: PowerAction) -> bool {
+        self.policy(action) != ActionPolicy::Deny && self.in_allowed_hours()
+    }
+
+    /// Whether the action has to be confirmed by the user before it runs
+    pub fn requires_confirmation(&self, action: PowerAction) -> bool {
+        self.policy(action) == ActionPolicy::Confirm
+    }
+
+    fn in_allowed_hours(&self) -> bool {
+        if self.allowed_hours.is_empty() {
+            return true;
+        }
+        let now = match DateTime::now_local() {
+            Ok(now) => now.hour() as u32 * 60 + now.minute() as u32,
+            Err(e) => {
+                log::error!("Failed to get local time: {e}");
+                return false;
+            }
+        };
+        self.allowed_hours
+            .iter()
+            .filter_map(|range| {
+                let parsed = parse_time_range(range);
+                if parsed.is_none() {
+                    log::error!("Invalid time range in [power] allowed_hours: \"{range}\"");
+                }
+                parsed
+            })
+            .any(|(start, end)| {
+                if start <= end {
+                    start <= now && now < end
+                } else {
+                    // range wraps around midnight, i.e. "22:00-06:00"
+                    now >= start || now < end
+                }
+            })
+    }
+}
+
+/// Parse "HH:MM-HH:MM" into minutes since midnight
+fn parse_time_range(range: &str) -> Option<(u32, u32)> {
+    let parse_time = |time: &str| {
+        let (h, m) = time.trim().split_once(':')?;
+        let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+        (h <= 24 && m < 60 && h * 60 + m <= 24 * 60).then_some(h * 60 + m)
+    };
+    let (start, end) = range.split_once('-')?;
+    Some((parse_time(start)?, parse_time(end)?))
+}
+
 pub struct PowerManager;
 
 impl PowerManager {
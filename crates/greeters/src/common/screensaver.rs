@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use super::dbus::LogindManager;
+
+pub struct Screensaver;
+
+impl Screensaver {
+    /// Power down the displays and mark the greeter session as idle
+    pub fn activate() {
+        set_displays_power(false);
+        set_idle_hint(true);
+    }
+
+    /// Power up the displays and mark the greeter session as active
+    pub fn deactivate() {
+        set_displays_power(true);
+        set_idle_hint(false);
+    }
+}
+
+fn set_idle_hint(idle: bool) {
+    let result = LogindManager::proxy()
+        .get_session_by_pid(std::process::id())
+        .and_then(LogindManager::session_proxy)
+        .and_then(|session| session.set_idle_hint(idle));
+    if let Err(e) = result {
+        log::error!("Failed to set logind idle hint to {idle}: {e}");
+    }
+}
+
+fn set_displays_power(on: bool) {
+    match std::env::var("XDG_SESSION_TYPE").as_deref() {
+        Ok("wayland") => match std::env::var("XDG_CURRENT_DESKTOP").as_deref() {
+            Ok("Hyprland") => hyprland::set_dpms(on)
+                .unwrap_or_else(|e| log::error!("Failed to set hyprland dpms: {e}")),
+            Ok("sway") => sway::set_output_power(on)
+                .unwrap_or_else(|e| log::error!("Failed to set sway output power: {e}")),
+            Ok(s) => {
+                log::warn!("WebKit Greeter does not support powering down displays of {s} yet")
+            }
+            Err(e) => {
+                log::error!("Could not get $XDG_CURRENT_DESKTOP environment variable: {e}")
+            }
+        },
+        Ok("x11") => x11::force_dpms_level(on)
+            .unwrap_or_else(|e| log::error!("Failed to set X11 DPMS level: {e}")),
+        _ => log::error!("Could not get $XDG_SESSION_TYPE environment variable"),
+    }
+}
+
+mod hyprland {
+    use hyprland::dispatch::{Dispatch, DispatchType};
+
+    pub(super) fn set_dpms(on: bool) -> Result<(), hyprland::error::HyprError> {
+        Dispatch::call(DispatchType::Custom("dpms", if on { "on" } else { "off" }))
+    }
+}
+
+mod sway {
+    pub(super) fn set_output_power(on: bool) -> Result<(), swayipc::Error> {
+        let power = if on { "on" } else { "off" };
+        swayipc::Connection::new()?
+            .run_command(format!("output * power {power}"))?
+            .into_iter()
+            .collect()
+    }
+}
+
+mod x11 {
+    use x11rb::{
+        connection::RequestConnection,
+        protocol::dpms::{ConnectionExt, DPMSMode},
+    };
+
+    pub(super) fn force_dpms_level(on: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let (conn, _) = x11rb::connect(None)?;
+        if conn
+            .extension_information(x11rb::protocol::dpms::X11_EXTENSION_NAME)?
+            .is_none()
+        {
+            return Err("DPMS extension is not available".into());
+        }
+        let level = if on { DPMSMode::ON } else { DPMSMode::OFF };
+        conn.dpms_enable()?.check()?;
+        conn.dpms_force_level(level)?.check()?;
+        Ok(())
+    }
+}
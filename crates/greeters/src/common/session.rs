@@ -2,40 +2,191 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
-use serde::Serialize;
-use webkit::glib::{
-    KEY_FILE_DESKTOP_GROUP, KEY_FILE_DESKTOP_KEY_HIDDEN, KEY_FILE_DESKTOP_KEY_NO_DISPLAY,
-    KEY_FILE_DESKTOP_KEY_TRY_EXEC, KeyFile, KeyFileFlags, find_program_in_path, system_data_dirs,
+use gtk::{gdk, prelude::*};
+use serde::{Deserialize, Serialize};
+use webkit::{
+    gio,
+    glib::{
+        self, KEY_FILE_DESKTOP_GROUP, KEY_FILE_DESKTOP_KEY_HIDDEN, KEY_FILE_DESKTOP_KEY_ICON,
+        KEY_FILE_DESKTOP_KEY_NO_DISPLAY, KEY_FILE_DESKTOP_KEY_TRY_EXEC, KeyFile, KeyFileFlags,
+        SourceId, find_program_in_path, shell_parse_argv, shell_quote, system_data_dirs,
+    },
 };
 
-use std::{collections::HashMap, fs::read_dir, path::PathBuf, sync::OnceLock};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap},
+    fs::read_dir,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{OnceLock, RwLock},
+    time::Duration,
+};
+
+use super::LanguageManager;
+use crate::config::Config;
+
+/// `[[sessions]]` entry of webkit-greeter.toml, a session that has no desktop file
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomSession {
+    /// Key of the session, the one of a session file to replace or hide it
+    key: String,
+    name: String,
+    /// "wayland", "x" or "tty"
+    #[serde(rename = "type")]
+    type_: String,
+    /// Command line run by the display manager
+    command: String,
+    /// Additional environment variables of the session
+    environment: BTreeMap<String, String>,
+    /// Hide the session file of this key instead of adding a session
+    hidden: bool,
+}
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Session {
+    /// Unique among all sessions, the file name of the desktop file without ".desktop",
+    /// suffixed with "-x11" for an X session that has a Wayland session of the same file name
     key: String,
+    /// File name of the desktop file without ".desktop"
+    #[serde(skip_serializing)]
+    id: String,
     #[serde(rename(serialize = "type"))]
     type_: String,
     name: String,
     comment: String,
+    /// Name of each locale the desktop file has a translated Name for, i.e. "de" => "Sitzung"
+    names: BTreeMap<String, String>,
+    /// Comment of each locale the desktop file has a translated Comment for
+    comments: BTreeMap<String, String>,
+    /// DesktopNames, the value of XDG_CURRENT_DESKTOP in the session
+    desktop_names: Vec<String>,
+    /// Path of the Icon file, looked up in the icon theme unless it is absolute
+    icon: Option<String>,
+    /// Path of the TryExec program, found in PATH
+    try_exec: Option<String>,
+    /// X-GDM-* and X-LightDM-* keys, i.e. "X-GDM-SessionRegisters" => "true"
+    extensions: BTreeMap<String, String>,
+    /// Exec starts the compositor as a systemd unit through `uwsm start`
+    uwsm: bool,
+    /// The session can be started either through uwsm or directly
+    uwsm_available: bool,
     #[serde(skip_serializing)]
     exec: String,
+    /// Additional environment variables, only set by custom sessions
+    #[serde(skip_serializing)]
+    environment: BTreeMap<String, String>,
+    /// Desktop file of the session, empty for custom sessions
+    #[serde(skip_serializing)]
+    path: PathBuf,
 }
 
 impl Session {
-    fn new(key: String, type_: String, name: String, comment: String, exec: String) -> Self {
+    fn new(id: String, type_: String, keyfile: &KeyFile, path: PathBuf) -> Self {
+        let string = |key: &str| keyfile.string(KEY_FILE_DESKTOP_GROUP, key).ok();
+        let keys: Vec<String> = keyfile
+            .keys(KEY_FILE_DESKTOP_GROUP)
+            .map(|keys| keys.iter().map(|k| k.to_string()).collect())
+            .unwrap_or_default();
+        let translations = |name: &str| -> BTreeMap<String, String> {
+            keys.iter()
+                .filter_map(|key| {
+                    let locale = key
+                        .strip_prefix(name)?
+                        .strip_prefix('[')?
+                        .strip_suffix(']')?;
+                    Some((locale.to_string(), string(key)?.into()))
+                })
+                .collect()
+        };
+        let extensions = keys
+            .iter()
+            .filter(|key| key.starts_with("X-GDM-") || key.starts_with("X-LightDM-"))
+            .filter_map(|key| Some((key.clone(), string(key)?.into())))
+            .collect();
+        let desktop_names = keyfile
+            .string_list(KEY_FILE_DESKTOP_GROUP, "DesktopNames")
+            .map(|names| names.iter().map(|n| n.to_string()).collect())
+            .unwrap_or_default();
+        let exec: String = string("Exec").map(Into::into).unwrap_or_default();
         Self {
-            key,
+            key: id.clone(),
+            id,
             type_,
-            name,
-            comment,
+            name: keyfile
+                .locale_string(KEY_FILE_DESKTOP_GROUP, "Name", None)
+                .map(Into::into)
+                .unwrap_or_default(),
+            comment: keyfile
+                .locale_string(KEY_FILE_DESKTOP_GROUP, "Comment", None)
+                .map(Into::into)
+                .unwrap_or_default(),
+            names: translations("Name"),
+            comments: translations("Comment"),
+            desktop_names,
+            icon: string(KEY_FILE_DESKTOP_KEY_ICON).and_then(|icon| icon_path(&icon)),
+            try_exec: string(KEY_FILE_DESKTOP_KEY_TRY_EXEC)
+                .and_then(find_program_in_path)
+                .map(|path| path.to_string_lossy().into_owned()),
+            extensions,
+            uwsm: uwsm_target(&exec).is_some(),
+            uwsm_available: uwsm_available(&type_),
             exec,
+            environment: BTreeMap::new(),
+            path,
         }
     }
 
+    fn custom(custom: &CustomSession) -> Self {
+        Self {
+            key: custom.key.clone(),
+            id: custom.key.clone(),
+            type_: custom.type_.clone(),
+            name: custom.name.clone(),
+            comment: String::new(),
+            names: BTreeMap::new(),
+            comments: BTreeMap::new(),
+            desktop_names: vec![],
+            icon: None,
+            try_exec: None,
+            extensions: BTreeMap::new(),
+            uwsm: uwsm_target(&custom.command).is_some(),
+            uwsm_available: uwsm_available(&custom.type_),
+            exec: custom.command.clone(),
+            environment: custom.environment.clone(),
+            path: PathBuf::new(),
+        }
+    }
+
+    /// Copy with Name and Comment in the language of the greeter
+    pub fn localized(&self) -> Session {
+        let mut session = self.clone();
+        let Some(locale) = LanguageManager::greeter_language() else {
+            return session;
+        };
+        let locales = locale_keys(&locale);
+        let translated = |translations: &BTreeMap<String, String>| {
+            locales.iter().find_map(|l| translations.get(l)).cloned()
+        };
+        if let Some(name) = translated(&self.names) {
+            session.name = name;
+        }
+        if let Some(comment) = translated(&self.comments) {
+            session.comment = comment;
+        }
+        session
+    }
+
     pub fn key(&self) -> &str {
         &self.key
     }
 
+    /// Name of the session known to the display manager, i.e. the key without "-x11"
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
     pub fn type_(&self) -> &str {
         &self.type_
     }
@@ -51,38 +202,340 @@ impl Session {
     pub fn exec(&self) -> &str {
         &self.exec
     }
+
+    /// Whether the session is defined in webkit-greeter.toml instead of a desktop file
+    pub fn is_custom(&self) -> bool {
+        self.path.as_os_str().is_empty()
+    }
+
+    pub fn environment(&self) -> &BTreeMap<String, String> {
+        &self.environment
+    }
+
+    pub fn desktop_names(&self) -> &[String] {
+        &self.desktop_names
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    pub fn try_exec(&self) -> Option<&str> {
+        self.try_exec.as_deref()
+    }
+
+    /// Value of an X-GDM-* or X-LightDM-* key
+    pub fn extension(&self, key: &str) -> Option<&str> {
+        self.extensions.get(key).map(String::as_str)
+    }
+
+    pub fn uwsm(&self) -> bool {
+        self.uwsm
+    }
+
+    /// Command line of the session, run as a uwsm-managed systemd unit if uwsm is true,
+    /// or directly if uwsm is false. The Exec is kept as is if uwsm is None.
+    pub fn command(&self, uwsm: Option<bool>) -> String {
+        match uwsm {
+            Some(uwsm) if uwsm == self.uwsm => self.exec.clone(),
+            Some(true) if !self.uwsm_available => {
+                log::warn!("Session \"{}\" cannot be started through uwsm", self.key);
+                self.exec.clone()
+            }
+            Some(true) if self.is_custom() => format!("uwsm start -- {}", self.exec),
+            Some(true) => format!(
+                "uwsm start -- {}",
+                shell_quote(format!("{}.desktop", self.id)).to_string_lossy()
+            ),
+            Some(false) => self.direct_command(),
+            None => self.exec.clone(),
+        }
+    }
+
+    /// Command line that uwsm runs for a uwsm-managed session
+    fn direct_command(&self) -> String {
+        let Some(target) = uwsm_target(&self.exec) else {
+            return self.exec.clone();
+        };
+        if let [entry] = target.as_slice()
+            && let Some(id) = entry.strip_suffix(".desktop")
+        {
+            return match SessionManager::session(id).filter(|s| !s.uwsm) {
+                Some(session) => session.exec.clone(),
+                None => {
+                    log::warn!("Session \"{id}\" started by uwsm is not found");
+                    self.exec.clone()
+                }
+            };
+        }
+        target
+            .iter()
+            .map(|arg| shell_quote(arg).to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Locale keys matching a locale, from the most specific one, as the Desktop Entry
+/// Specification orders them, i.e. "sr_RS.UTF-8@latin" => "sr_RS@latin", "sr_RS", "sr@latin", "sr"
+fn locale_keys(locale: &str) -> Vec<String> {
+    let (name, modifier) = match locale.split_once('@') {
+        Some((name, modifier)) => (name, Some(modifier)),
+        None => (locale, None),
+    };
+    let name = name.split_once('.').map_or(name, |(name, _)| name);
+    let lang = name.split_once('_').map_or(name, |(lang, _)| lang);
+
+    let mut keys = vec![];
+    for base in [Some(name), (lang != name).then_some(lang)]
+        .into_iter()
+        .flatten()
+    {
+        if let Some(modifier) = modifier {
+            keys.push(format!("{base}@{modifier}"));
+        }
+        keys.push(base.to_string());
+    }
+    keys
+}
+
+/// Whether a session of this type can be managed by uwsm
+fn uwsm_available(session_type: &str) -> bool {
+    session_type == "wayland" && find_program_in_path("uwsm").is_some()
+}
+
+/// Compositor of an Exec like "uwsm start -D Hyprland -- hyprland.desktop", either one desktop
+/// entry or a command with its arguments
+fn uwsm_target(exec: &str) -> Option<Vec<String>> {
+    let argv: Vec<String> = shell_parse_argv(exec)
+        .ok()?
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let program = Path::new(argv.first()?).file_name()?;
+    if program != "uwsm" || argv.get(1).map(String::as_str) != Some("start") {
+        return None;
+    }
+    let mut args = argv[2..].iter().enumerate();
+    while let Some((i, arg)) = args.next() {
+        match arg.as_str() {
+            "--" => return Some(argv[i + 3..].to_vec()).filter(|t| !t.is_empty()),
+            // options followed by a value
+            "-D" | "-N" | "-C" | "-t" => {
+                args.next();
+            }
+            arg if arg.starts_with('-') => {}
+            _ => return Some(argv[i + 2..].to_vec()),
+        }
+    }
+    None
+}
+
+/// Icon file of an Icon key, which is either an absolute path or an icon name
+fn icon_path(icon: &str) -> Option<String> {
+    if Path::new(icon).is_absolute() {
+        return Path::new(icon).is_file().then(|| icon.to_string());
+    }
+    if !gtk::is_initialized_main_thread() {
+        return None;
+    }
+    let display = gdk::Display::default()?;
+    let theme = gtk::IconTheme::for_display(&display);
+    if !theme.has_icon(icon) {
+        return None;
+    }
+    theme
+        .lookup_icon(
+            icon,
+            &[],
+            SESSION_ICON_SIZE,
+            1,
+            gtk::TextDirection::None,
+            gtk::IconLookupFlags::empty(),
+        )
+        .file()?
+        .path()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Size in pixels of session icons looked up in the icon theme
+const SESSION_ICON_SIZE: i32 = 64;
+
+/// Suffix of the key of an X session whose desktop file name is also used by a Wayland session
+const X11_KEY_SUFFIX: &str = "-x11";
+
+/// Time to wait for more changes of session files before reloading the sessions
+const SESSIONS_RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+thread_local! {
+    /// Monitors of the session directories, alive as long as the greeter
+    static SESSION_MONITORS: RefCell<Vec<gio::FileMonitor>> = const { RefCell::new(vec![]) };
 }
 
 pub struct SessionManager;
 impl SessionManager {
-    // TODO: handle duplicated sessions
-    fn available_sessions_map() -> &'static HashMap<String, Session> {
-        static SESSION_FILES: OnceLock<HashMap<String, Session>> = OnceLock::new();
-        SESSION_FILES.get_or_init(|| {
-            system_data_dirs() // ["/usr/local/share", "/usr/share"]
-                .iter()
-                .flat_map(|dir| {
-                    [
-                        load_session_dir(dir.join("xsessions"), "x"),
-                        load_session_dir(dir.join("wayland-sessions"), "wayland"),
-                    ]
-                    .into_iter()
-                    .flatten()
-                    .collect::<HashMap<_, _>>()
-                })
-                .collect()
-        })
+    fn available_sessions() -> &'static RwLock<HashMap<String, Session>> {
+        static SESSIONS: OnceLock<RwLock<HashMap<String, Session>>> = OnceLock::new();
+        SESSIONS.get_or_init(|| RwLock::new(load_sessions()))
     }
 
-    pub fn sessions() -> Vec<&'static Session> {
-        let mut sessions: Vec<_> = Self::available_sessions_map().values().collect();
-        sessions.sort_by_key(|s| s.key());
+    pub fn sessions() -> Vec<Session> {
+        let mut sessions: Vec<_> = Self::available_sessions()
+            .read()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect();
+        sessions.sort_by(|a, b| a.key().cmp(b.key()));
         sessions
     }
 
-    pub fn session(key: &str) -> Option<&Session> {
-        Self::available_sessions_map().get(key)
+    pub fn session(key: &str) -> Option<Session> {
+        Self::available_sessions().read().unwrap().get(key).cloned()
+    }
+
+    /// Whether LightDM starts another session for the file name of this one. LightDM
+    /// looks sessions up by file name only, in xsessions before wayland-sessions, so it
+    /// cannot start the Wayland session of an X and a Wayland session of the same file name.
+    pub fn shadowed_in_lightdm(session: &Session) -> bool {
+        session.type_() == "wayland"
+            && Self::available_sessions()
+                .read()
+                .unwrap()
+                .values()
+                .any(|s| s.id == session.id && s.type_() == "x" && !s.is_custom())
+    }
+
+    /// Key of the session that AccountsService stores by desktop file name and session type
+    pub fn session_key(id: &str, session_type: Option<&str>) -> String {
+        let session_type = match session_type {
+            Some("x11") => Some("x"),
+            session_type => session_type,
+        };
+        let sessions = Self::available_sessions().read().unwrap();
+        let mut candidates: Vec<_> = sessions.values().filter(|s| s.id == id).collect();
+        candidates.sort_by_key(|s| (Some(s.type_()) != session_type, s.key != s.id));
+        candidates
+            .first()
+            .map(|s| s.key.clone())
+            .unwrap_or(id.to_string())
+    }
+
+    /// Reload the sessions whenever a session directory changes, i.e. a desktop environment
+    /// is installed, `f` is called on the main context after every reload.
+    pub fn connect_sessions_changed<F: Fn() + 'static>(f: F) {
+        let f = Rc::new(f);
+        let pending: Rc<Cell<Option<SourceId>>> = Rc::default();
+        let monitors: Vec<_> = system_data_dirs()
+            .iter()
+            .flat_map(|dir| [dir.join("wayland-sessions"), dir.join("xsessions")])
+            .filter_map(|dir| {
+                match gio::File::for_path(&dir)
+                    .monitor_directory(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+                {
+                    Ok(monitor) => Some(monitor),
+                    Err(e) => {
+                        log::warn!("Failed to watch {dir:?}: {e}");
+                        None
+                    }
+                }
+            })
+            .collect();
+        for monitor in &monitors {
+            let f = f.clone();
+            let pending = pending.clone();
+            monitor.connect_changed(move |_, file, _, event| {
+                if event == gio::FileMonitorEvent::Changed {
+                    // wait for ChangesDoneHint
+                    return;
+                }
+                log::debug!("Session file {:?} changed: {event:?}", file.path());
+                // a package manager touches several files at once, reload after the last one
+                if let Some(source) = pending.take() {
+                    source.remove();
+                }
+                let f = f.clone();
+                let pending_ = pending.clone();
+                pending.set(Some(glib::timeout_add_local_once(
+                    SESSIONS_RELOAD_DELAY,
+                    move || {
+                        pending_.set(None);
+                        *Self::available_sessions().write().unwrap() = load_sessions();
+                        f();
+                    },
+                )));
+            });
+        }
+        SESSION_MONITORS.with(|m| m.borrow_mut().extend(monitors));
+    }
+}
+
+/// Sessions of all data directories by key.
+///
+/// Like any other XDG data file, a session file of an earlier data directory overrides
+/// the one of the same type and file name in a later directory. If an X session and
+/// a Wayland session share a file name, the Wayland session keeps it as key.
+/// Custom sessions of the configuration are added last, replacing or hiding the
+/// session of the same key.
+fn load_sessions() -> HashMap<String, Session> {
+    let data_dirs = system_data_dirs(); // ["/usr/local/share", "/usr/share"]
+    let mut sessions: HashMap<String, Session> = HashMap::new();
+    for (subdir, session_type) in [("wayland-sessions", "wayland"), ("xsessions", "x")] {
+        // the earliest file of an id wins before its visibility is considered,
+        // so that a Hidden file hides the file of the same name in a later directory
+        let mut found: BTreeMap<String, (PathBuf, Option<Session>)> = BTreeMap::new();
+        for dir in &data_dirs {
+            for (id, path, session) in load_session_dir(dir.join(subdir), session_type) {
+                match found.get(&id) {
+                    Some((winner, _)) => log::info!("Session {path:?} is shadowed by {winner:?}"),
+                    None => {
+                        found.insert(id, (path, session));
+                    }
+                }
+            }
+        }
+        for mut session in found.into_values().filter_map(|(_, session)| session) {
+            if sessions.contains_key(&session.key) {
+                session.key = format!("{}{X11_KEY_SUFFIX}", session.id);
+                log::info!(
+                    "X session {:?} has the key \"{}\" beside Wayland session \"{}\"",
+                    session.path,
+                    session.key,
+                    session.id
+                );
+            }
+            if let Some(other) = sessions.get(&session.key) {
+                log::warn!(
+                    "Session {:?} is shadowed by {:?} with the same key",
+                    session.path,
+                    other.path
+                );
+                continue;
+            }
+            sessions.insert(session.key.clone(), session);
+        }
+    }
+    for custom in Config::instance().sessions() {
+        if custom.key.is_empty() {
+            log::warn!("Ignoring a custom session without key");
+        } else if custom.hidden {
+            if sessions.remove(&custom.key).is_none() {
+                log::info!("No session \"{}\" to hide", custom.key);
+            }
+        } else if custom.command.is_empty() {
+            log::warn!("Custom session \"{}\" has no command", custom.key);
+        } else {
+            if let Some(session) = sessions.get(&custom.key) {
+                log::info!(
+                    "Session {:?} is replaced by custom session \"{}\"",
+                    session.path,
+                    custom.key
+                );
+            }
+            sessions.insert(custom.key.clone(), Session::custom(custom));
+        }
     }
+    sessions
 }
 
 fn is_session_desktop_file(keyfile: &KeyFile) -> bool {
@@ -98,9 +551,11 @@ fn is_session_desktop_file(keyfile: &KeyFile) -> bool {
     !no_display && !hidden && !tryexec_failed
 }
 
-fn load_session_dir(dir: PathBuf, session_type: &str) -> HashMap<String, Session> {
+/// Desktop files of a session directory with their id, the session is None
+/// if the file is hidden or cannot be used
+fn load_session_dir(dir: PathBuf, session_type: &str) -> Vec<(String, PathBuf, Option<Session>)> {
     if !dir.is_dir() {
-        return HashMap::with_capacity(0);
+        return Vec::with_capacity(0);
     }
     read_dir(dir)
         .unwrap()
@@ -112,12 +567,18 @@ fn load_session_dir(dir: PathBuf, session_type: &str) -> HashMap<String, Session
             if let Err(e) = keyfile.load_from_file(&filepath, KeyFileFlags::NONE) {
                 log::warn!("Failed to load \"{filepath_str}\": {e}");
             } else if keyfile.has_group(KEY_FILE_DESKTOP_GROUP) {
+                let id = file
+                    .file_name()
+                    .to_string_lossy()
+                    .trim_end_matches(".desktop")
+                    .to_string();
                 if !is_session_desktop_file(&keyfile) {
                     log::warn!(
                         "\"{filepath_str}\" is hidden, {}, {}",
                         "contains non-executable TryExec program",
                         "or is otherwise not capable of being used"
                     );
+                    return Some((id, filepath, None));
                 } else if keyfile
                     .has_key(KEY_FILE_DESKTOP_GROUP, "Name")
                     .is_ok_and(|b| b)
@@ -125,29 +586,12 @@ fn load_session_dir(dir: PathBuf, session_type: &str) -> HashMap<String, Session
                         .has_key(KEY_FILE_DESKTOP_GROUP, "Exec")
                         .is_ok_and(|b| b)
                 {
-                    let key = file
-                        .file_name()
-                        .to_string_lossy()
-                        .trim_end_matches(".desktop")
-                        .to_string();
-                    let name = keyfile
-                        .locale_string(KEY_FILE_DESKTOP_GROUP, "Name", None)
-                        .unwrap()
-                        .into();
-                    let comment = keyfile
-                        .locale_string(KEY_FILE_DESKTOP_GROUP, "Comment", None)
-                        .unwrap_or_default()
-                        .into();
-                    let exec = keyfile
-                        .string(KEY_FILE_DESKTOP_GROUP, "Exec")
-                        .unwrap_or_default()
-                        .into();
-                    return Some((
-                        key.clone(),
-                        Session::new(key, session_type.into(), name, comment, exec),
-                    ));
+                    let session =
+                        Session::new(id.clone(), session_type.into(), &keyfile, filepath.clone());
+                    return Some((id, filepath, Some(session)));
                 } else {
                     log::warn!("{filepath_str} contains no \"Name\" or \"Exec\" key");
+                    return Some((id, filepath, None));
                 }
             }
             None
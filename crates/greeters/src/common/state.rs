@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+use webkit::glib::user_data_dir;
+
+use std::{collections::HashMap, path::PathBuf};
+
+/// Choices the greeter remembers by itself, kept in the data directory of the greeter user
+/// for users that are not managed by AccountsService
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GreeterState {
+    /// Keyboard layouts of each user, the last chosen one first
+    layouts: HashMap<String, Vec<String>>,
+}
+
+impl GreeterState {
+    fn path() -> PathBuf {
+        user_data_dir().join("webkit-greeter").join("state.json")
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(Self::path()) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Failed to parse greeter state: {e}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let path = Self::path();
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| std::fs::write(&path, serde_json::to_string(self).unwrap()));
+        if let Err(e) = result {
+            log::warn!("Failed to save greeter state to {path:?}: {e}");
+        }
+    }
+
+    pub fn layouts(&self, username: &str) -> &[String] {
+        self.layouts.get(username).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Move layout to the front of the layouts of user
+    pub fn set_layout(&mut self, username: &str, layout: &str) {
+        let layouts = self.layouts.entry(username.to_string()).or_default();
+        layouts.retain(|l| l != layout);
+        layouts.insert(0, layout.to_string());
+    }
+}
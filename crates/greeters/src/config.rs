@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde::Deserialize;
+
+use std::sync::OnceLock;
+
+use crate::{
+    common::{
+        CustomSession, KeyboardSettings, LanguageOrder, LayoutFilter, PowerPolicy, UserFilter,
+    },
+    greeters::GreetdSettings,
+};
+
+/// Sections of webkit-greeter.toml that are handled by the greeters
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    greetd: GreetdSettings,
+    keyboard: KeyboardSettings,
+    languages: LanguageOrder,
+    layouts: LayoutFilter,
+    power: PowerPolicy,
+    sessions: Vec<CustomSession>,
+    users: UserFilter,
+    /// Fallback avatar, taken from `user_image` of the `[branding]` section
+    #[serde(skip)]
+    default_avatar: Option<String>,
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+impl Config {
+    /// Set the configuration used by the greeters, only the first call takes effect
+    pub fn init(config: Config) {
+        if CONFIG.set(config).is_err() {
+            log::warn!("Greeters configuration is already initialized");
+        }
+    }
+
+    pub fn instance() -> &'static Config {
+        CONFIG.get_or_init(Config::default)
+    }
+
+    pub fn greetd(&self) -> &GreetdSettings {
+        &self.greetd
+    }
+
+    pub fn keyboard(&self) -> &KeyboardSettings {
+        &self.keyboard
+    }
+
+    pub fn languages(&self) -> &LanguageOrder {
+        &self.languages
+    }
+
+    pub fn layouts(&self) -> &LayoutFilter {
+        &self.layouts
+    }
+
+    pub fn power(&self) -> &PowerPolicy {
+        &self.power
+    }
+
+    pub fn sessions(&self) -> &[CustomSession] {
+        &self.sessions
+    }
+
+    pub fn users(&self) -> &UserFilter {
+        &self.users
+    }
+
+    pub fn default_avatar(&self) -> Option<&str> {
+        self.default_avatar.as_deref()
+    }
+
+    pub fn set_default_avatar(&mut self, path: &str) {
+        self.default_avatar = Some(path.to_string()).filter(|p| !p.is_empty());
+    }
+}
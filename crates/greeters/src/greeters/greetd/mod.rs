@@ -6,12 +6,18 @@ use webkit::{WebView, glib::clone};
 
 use std::cell::RefCell;
 
-use crate::common::SessionManager;
+use crate::{
+    common::{Layout, LayoutManager, SessionManager, UserManager},
+    config::Config,
+};
 
 use super::signals;
 
 mod client;
+mod xorg;
 use client::GreetdClient;
+pub use xorg::GreetdSettings;
+use xorg::xorg_command;
 
 pub struct GreetdGreeter {
     greeter: RefCell<GreetdClient>,
@@ -62,7 +68,6 @@ impl GreetdGreeter {
             match method {
                 "authenticate" => self.create_session(args[0].as_str()),
                 "respond" => self.respond(args[0].as_str()),
-                "start_session" => self.start_session(args[0].as_str().as_ref().unwrap()),
                 s => {
                     log::warn!("{s} does not implemented");
                     "undefined".to_string()
@@ -87,6 +92,15 @@ impl GreetdGreeter {
         self.greeter.borrow().is_authenticated().to_string()
     }
 
+    /// User that has passed authentication
+    pub(super) fn authenticated_user(&self) -> Option<String> {
+        let greeter = self.greeter.borrow();
+        greeter
+            .is_authenticated()
+            .then(|| greeter.authentication_user().map(str::to_string))
+            .flatten()
+    }
+
     fn create_session(&self, username: Option<&str>) -> String {
         if let Err(e) = self
             .greeter
@@ -119,21 +133,71 @@ impl GreetdGreeter {
         true.to_string()
     }
 
-    fn start_session(&self, session_key: &str) -> String {
+    pub(super) fn start_session(
+        &self,
+        session_key: &str,
+        uwsm: Option<bool>,
+        language: Option<&str>,
+        layout: Option<&Layout>,
+    ) -> String {
         let session = SessionManager::session(session_key);
         if session.is_none() {
             log::error!("{session_key} does not exist");
             return false.to_string();
         }
         let session = session.unwrap();
-        let cmd = vec![session.exec().to_string()];
-        let env = match session.type_() {
+        let exec = match session.type_() {
+            "x" => xorg_command(Config::instance().greetd().xorg_wrapper(), session.exec()),
+            _ => Some(session.command(uwsm)),
+        };
+        let Some(exec) = exec else {
+            return false.to_string();
+        };
+        let cmd = vec![exec];
+        let mut env = match session.type_() {
             "wayland" => vec!["XDG_SESSION_TYPE=wayland".to_string()],
             "x" => vec!["XDG_SESSION_TYPE=x11".to_string()],
+            "tty" => vec!["XDG_SESSION_TYPE=tty".to_string()],
             _ => vec![],
         };
+        if !session.desktop_names().is_empty() {
+            env.push(format!(
+                "XDG_CURRENT_DESKTOP={}",
+                session.desktop_names().join(":")
+            ));
+        }
+        env.extend(
+            session
+                .environment()
+                .iter()
+                .map(|(name, value)| format!("{name}={value}")),
+        );
+        if let Some(language) = language {
+            env.push(format!("LANG={language}"));
+        }
+        // the keymap chosen in the greeter, for compositors that take it from the environment
+        if let Some(layout) = layout {
+            let (xkb_layout, xkb_variant) = layout.layout_variant();
+            env.push(format!("XKB_DEFAULT_LAYOUT={xkb_layout}"));
+            if !xkb_variant.is_empty() {
+                env.push(format!("XKB_DEFAULT_VARIANT={xkb_variant}"));
+            }
+            if let Some(xkb_options) = LayoutManager::instance().xkb_options() {
+                env.push(format!("XKB_DEFAULT_OPTIONS={xkb_options}"));
+            }
+        }
+        let username = self
+            .greeter
+            .borrow()
+            .authentication_user()
+            .map(str::to_string);
         match self.greeter.borrow_mut().start_session(cmd, env) {
-            Ok(()) => std::process::exit(0),
+            Ok(()) => {
+                if let Some(username) = username {
+                    UserManager::remember_choices(&username, Some(&session), language, layout);
+                }
+                std::process::exit(0)
+            }
             Err(e) => {
                 log::error!("{e}");
                 false.to_string()
@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+use webkit::glib::shell_quote;
+
+use std::path::Path;
+
+const DEFAULT_XORG_WRAPPER: &str = "startx /bin/sh -c {command} -- :{display} vt{vt} -keeptty";
+
+/// `[greetd]` section of webkit-greeter.toml
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GreetdSettings {
+    /// Command line that starts an X server with an X session,
+    /// where {command}, {vt} and {display} are replaced
+    xorg_wrapper: String,
+}
+
+impl Default for GreetdSettings {
+    fn default() -> Self {
+        Self {
+            xorg_wrapper: DEFAULT_XORG_WRAPPER.to_string(),
+        }
+    }
+}
+
+impl GreetdSettings {
+    pub fn xorg_wrapper(&self) -> &str {
+        &self.xorg_wrapper
+    }
+}
+
+/// Command line of an X session, its Exec wrapped in the configured Xorg wrapper
+pub(super) fn xorg_command(wrapper: &str, exec: &str) -> Option<String> {
+    if wrapper.trim().is_empty() {
+        return Some(exec.to_string());
+    }
+    let mut command = wrapper.to_string();
+    if command.contains("{vt}") {
+        let Some(vt) = session_vt() else {
+            log::error!("Failed to find the VT of the X session");
+            return None;
+        };
+        command = command.replace("{vt}", &vt.to_string());
+    }
+    if command.contains("{display}") {
+        command = command.replace("{display}", &free_display().to_string());
+    }
+    // greetd runs the command line through a shell
+    let exec = shell_quote(exec);
+    Some(command.replace("{command}", &exec.to_string_lossy()))
+}
+
+/// VT that greetd starts the session on, the one of the greeter
+fn session_vt() -> Option<u32> {
+    if let Some(vt) = std::env::var("XDG_VTNR")
+        .ok()
+        .and_then(|vt| vt.parse().ok())
+    {
+        return Some(vt);
+    }
+    std::fs::read_to_string("/sys/class/tty/tty0/active")
+        .ok()?
+        .trim()
+        .strip_prefix("tty")?
+        .parse()
+        .ok()
+}
+
+/// Lowest display number that no X server uses
+fn free_display() -> u32 {
+    (0..)
+        .find(|n| {
+            !Path::new(&format!("/tmp/.X{n}-lock")).exists()
+                && !Path::new(&format!("/tmp/.X11-unix/X{n}")).exists()
+        })
+        .unwrap()
+}
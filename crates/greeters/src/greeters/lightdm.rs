@@ -8,6 +8,7 @@ use webkit::{
 };
 
 use super::signals;
+use crate::common::{Layout, SessionManager, UserManager};
 
 use lightdm_client::Greeter;
 
@@ -57,7 +58,6 @@ impl LightDMGreeter {
                 "autologin_user" => self.autologin_user_hint(),
                 "default_session" => self.default_session_hint(),
                 "has_guest_account" => self.has_guest_account_hint(),
-                "hide_users_hint" => self.hide_users_hint(),
                 "lock_hint" => self.lock_hint(),
                 "select_guest_hint" => self.select_guest_hint(),
                 "select_user_hint" => self.select_user_hint(),
@@ -74,7 +74,6 @@ impl LightDMGreeter {
             match method {
                 "authenticate" => self.authenticate(args[0].as_str().map(str::to_string)),
                 "respond" => self.respond(args[0].as_str().unwrap().to_string()),
-                "start_session" => self.start_session_sync(args[0].as_str().map(str::to_string)),
                 s => {
                     log::warn!("{s} does not implemented");
                     "undefined".to_string()
@@ -99,6 +98,14 @@ impl LightDMGreeter {
         self.greeter.is_authenticated().to_string()
     }
 
+    /// User that has passed authentication
+    pub(super) fn authenticated_user(&self) -> Option<String> {
+        self.greeter
+            .is_authenticated()
+            .then(|| self.greeter.authentication_user())
+            .flatten()
+    }
+
     fn authenticate(&self, username: Option<String>) -> String {
         if let Err(e) = self.greeter.authenticate(username) {
             log::error!("{e}");
@@ -126,7 +133,49 @@ impl LightDMGreeter {
         }
     }
 
-    fn start_session_sync(&self, session: Option<String>) -> String {
+    pub(super) fn start_session_sync(
+        &self,
+        session: Option<String>,
+        language: Option<&str>,
+        layout: Option<&Layout>,
+    ) -> String {
+        let username = self.greeter.authentication_user();
+        let session_key = session.clone();
+        if let Some(key) = &session
+            && let Some(s) = SessionManager::session(key)
+        {
+            if s.is_custom() {
+                log::error!("LightDM cannot start the custom session \"{key}\"");
+                return false.to_string();
+            }
+            if SessionManager::shadowed_in_lightdm(&s) {
+                log::error!("LightDM starts the X session of the file name of \"{key}\" instead");
+                return false.to_string();
+            }
+        }
+        // LightDM sets up the session environment from the language and AccountsService
+        // when it starts the session, so the choices are applied beforehand
+        if let Some(language) = language
+            && let Err(e) = self.greeter.set_language(language)
+        {
+            log::error!("Failed to set the language of the session to {language}: {e}");
+        }
+        if let Some(username) = username {
+            UserManager::remember_choices(
+                &username,
+                session_key
+                    .as_deref()
+                    .and_then(SessionManager::session)
+                    .as_ref(),
+                language,
+                layout,
+            );
+        }
+        // LightDM only knows the file names of sessions
+        let session = session.map(|key| match SessionManager::session(&key) {
+            Some(session) => session.id().to_string(),
+            None => key,
+        });
         if let Err(e) =
             glib::MainContext::default().block_on(self.greeter.start_session_future(session))
         {
@@ -167,8 +216,8 @@ impl LightDMGreeter {
         self.greeter.has_guest_account_hint().to_string()
     }
 
-    fn hide_users_hint(&self) -> String {
-        self.greeter.hide_users_hint().to_string()
+    pub(super) fn hide_users_hint(&self) -> bool {
+        self.greeter.hide_users_hint()
     }
 
     fn lock_hint(&self) -> String {
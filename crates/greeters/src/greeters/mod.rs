@@ -6,14 +6,24 @@ mod greetd;
 mod lightdm;
 mod signals;
 
-pub use greetd::GreetdGreeter;
+pub use greetd::{GreetdGreeter, GreetdSettings};
 pub use lightdm::LightDMGreeter;
 
-use crate::common::{LanguageManager, LayoutManager, PowerManager, SessionManager, UserManager};
+use crate::{
+    common::{
+        Language, LanguageManager, Layout, LayoutManager, LockKeys, PowerAction, PowerManager,
+        Session, SessionManager, SessionsManager, UserManager,
+    },
+    config::Config,
+};
 
-use webkit::glib::variant::ToVariant;
-
-use webkit::glib::Variant;
+use gettextrs::gettext;
+use gtk::{AlertDialog, prelude::*};
+use std::cell::RefCell;
+use webkit::{
+    WebView,
+    glib::{self, Variant, variant::ToVariant},
+};
 
 use thiserror::Error as ThisError;
 #[derive(Debug, ThisError)]
@@ -38,20 +48,51 @@ pub struct Greeter {
     display_manager: String,
     greetd: Option<GreetdGreeter>,
     lightdm: Option<LightDMGreeter>,
+    /// Language chosen by set_language, applied to the session of the user
+    language: RefCell<Option<String>>,
+    webview: WebView,
 }
 
 impl Greeter {
-    pub fn new(webview: &webkit::WebView, display_manager: &str) -> Self {
+    pub fn new(webview: &WebView, display_manager: &str, config: Config) -> Self {
+        Config::init(config);
+        UserManager::instance().connect_users_changed(glib::clone!(
+            #[strong]
+            webview,
+            move || signals::users_changed(&webview)
+        ));
+        SessionManager::connect_sessions_changed(glib::clone!(
+            #[strong]
+            webview,
+            move || signals::sessions_changed(&webview)
+        ));
+        LayoutManager::instance().connect_layout_changed(glib::clone!(
+            #[strong]
+            webview,
+            move |layout| signals::layout_changed(&webview, &layout.localized())
+        ));
+        if Config::instance().keyboard().numlock() {
+            LayoutManager::instance().enable_num_lock();
+        }
+        LockKeys::connect_changed(glib::clone!(
+            #[strong]
+            webview,
+            move |lock_keys| signals::lock_keys_changed(&webview, &lock_keys)
+        ));
         match display_manager {
             "greetd" => Self {
                 display_manager: display_manager.to_string(),
                 greetd: Some(GreetdGreeter::new(webview)),
                 lightdm: None,
+                language: RefCell::new(None),
+                webview: webview.clone(),
             },
             "lightdm" => Self {
                 display_manager: display_manager.to_string(),
                 greetd: None,
                 lightdm: Some(LightDMGreeter::new(webview)),
+                language: RefCell::new(None),
+                webview: webview.clone(),
             },
             dm => unimplemented!("Unsupported display manager: {dm}"),
         }
@@ -75,7 +116,11 @@ impl Greeter {
                 "sessions" => self.sessions(),
                 "layout" => self.layout(),
                 "layouts" => self.layouts(),
+                "lock_keys" => self.lock_keys(),
                 "users" => self.users(),
+                "hide_users_hint" => self.hide_users_hint(),
+                "first_boot" => self.first_boot(),
+                "existing_sessions" => self.existing_sessions(),
                 m => match self.display_manager.as_str() {
                     "lightdm" => self.lightdm.as_ref().unwrap().handle(m, &[]),
                     "greetd" => self.greetd.as_ref().unwrap().handle(m, &[]),
@@ -83,8 +128,26 @@ impl Greeter {
                 },
             }
         } else {
+            // the password should be typed with the keymap of the selected user
+            if method == "authenticate"
+                && let Some(username) = args[0].as_str()
+            {
+                LayoutManager::instance().select_user(username);
+            }
             match method {
                 "layout" => self.set_layout(args[0].as_str().unwrap()),
+                "activate_existing_session" => {
+                    self.activate_existing_session(args[0].as_str().unwrap_or_default())
+                }
+                "set_language" => self.set_language(args[0].as_str().unwrap_or_default()),
+                "set_greeter_language" => {
+                    self.set_greeter_language(args[0].as_str().unwrap_or_default())
+                }
+                "create_first_user" => self.create_first_user(args),
+                "start_session" => self.start_session(
+                    args[0].as_str(),
+                    args.get(1).and_then(serde_json::Value::as_bool),
+                ),
                 m => match self.display_manager.as_str() {
                     "lightdm" => self.lightdm.as_ref().unwrap().handle(m, args),
                     "greetd" => self.greetd.as_ref().unwrap().handle(m, args),
@@ -96,65 +159,181 @@ impl Greeter {
     }
 
     fn can_hibernate(&self) -> String {
-        PowerManager::can_hibernate().to_string()
+        (Config::instance().power().permits(PowerAction::Hibernate)
+            && PowerManager::can_hibernate())
+        .to_string()
     }
 
     fn can_reboot(&self) -> String {
-        PowerManager::can_reboot().to_string()
+        (Config::instance().power().permits(PowerAction::Restart) && PowerManager::can_reboot())
+            .to_string()
     }
 
     fn can_shutdown(&self) -> String {
-        PowerManager::can_power_off().to_string()
+        (Config::instance().power().permits(PowerAction::Shutdown) && PowerManager::can_power_off())
+            .to_string()
     }
 
     fn can_suspend(&self) -> String {
-        PowerManager::can_suspend().to_string()
+        (Config::instance().power().permits(PowerAction::Suspend) && PowerManager::can_suspend())
+            .to_string()
     }
 
     fn hibernate(&self) -> String {
-        PowerManager::hibernate()
-            .inspect_err(|e| log::error!("{e}"))
-            .is_ok()
-            .to_string()
+        self.power_action(PowerAction::Hibernate, PowerManager::hibernate)
     }
 
     fn reboot(&self) -> String {
-        PowerManager::reboot()
-            .inspect_err(|e| log::error!("{e}"))
-            .is_ok()
-            .to_string()
+        self.power_action(PowerAction::Restart, PowerManager::reboot)
     }
 
     fn shutdown(&self) -> String {
-        PowerManager::power_off()
-            .inspect_err(|e| log::error!("{e}"))
-            .is_ok()
-            .to_string()
+        self.power_action(PowerAction::Shutdown, PowerManager::power_off)
     }
 
     fn suspend(&self) -> String {
-        PowerManager::suspend()
-            .inspect_err(|e| log::error!("{e}"))
-            .is_ok()
-            .to_string()
+        self.power_action(PowerAction::Suspend, PowerManager::suspend)
+    }
+
+    /// Run the power action if it is permitted and confirmed according to the `[power]` policy
+    fn power_action(&self, action: PowerAction, f: fn() -> zbus::Result<()>) -> String {
+        let policy = Config::instance().power();
+        if !policy.permits(action) {
+            log::warn!("{} is not permitted by the power policy", action.name());
+            return false.to_string();
+        }
+        if policy.requires_confirmation(action) && !self.confirm_power_action(action) {
+            log::debug!("{} was not confirmed", action.name());
+            return false.to_string();
+        }
+        f().inspect_err(|e| log::error!("{e}")).is_ok().to_string()
+    }
+
+    fn confirm_power_action(&self, action: PowerAction) -> bool {
+        let (message, button) = match action {
+            PowerAction::Hibernate => (gettext("Hibernate the computer?"), gettext("Hibernate")),
+            PowerAction::Restart => (gettext("Restart the computer?"), gettext("Restart")),
+            PowerAction::Shutdown => (gettext("Shut down the computer?"), gettext("Shut Down")),
+            PowerAction::Suspend => (gettext("Suspend the computer?"), gettext("Suspend")),
+        };
+        let window = self
+            .webview
+            .root()
+            .and_then(|root| root.downcast::<gtk::Window>().ok());
+        let dialog = AlertDialog::builder()
+            .message(message)
+            .buttons([gettext("Cancel"), button])
+            .cancel_button(0)
+            .default_button(0)
+            .modal(true)
+            .build();
+        matches!(
+            glib::MainContext::default().block_on(dialog.choose_future(window.as_ref())),
+            Ok(1)
+        )
     }
 
     fn languages(&self) -> String {
-        serde_json::to_string(LanguageManager::languages()).unwrap()
+        let mut languages: Vec<_> = LanguageManager::languages()
+            .iter()
+            .map(Language::localized)
+            .collect();
+        Config::instance().languages().sort(&mut languages);
+        serde_json::to_string(&languages).unwrap()
     }
 
     fn language(&self) -> String {
         match LanguageManager::current() {
-            Some(language) => serde_json::to_string(&language).unwrap(),
+            Some(language) => serde_json::to_string(&language.localized()).unwrap(),
             None => match LanguageManager::languages().first() {
-                Some(language) => serde_json::to_string(&language).unwrap(),
+                Some(language) => serde_json::to_string(&language.localized()).unwrap(),
                 None => "null".to_string(),
             },
         }
     }
 
+    fn first_boot(&self) -> String {
+        UserManager::first_boot_required().to_string()
+    }
+
+    fn create_first_user(&self, args: &[serde_json::Value]) -> String {
+        let arg = |i: usize| args.get(i).and_then(|a| a.as_str()).unwrap_or_default();
+        match UserManager::create_first_user(arg(0), arg(1), arg(2)) {
+            Ok(()) => true.to_string(),
+            Err(e) => {
+                log::error!("Failed to create the first user: {e}");
+                false.to_string()
+            }
+        }
+    }
+
+    fn set_language(&self, language: &str) -> String {
+        let prefix = language.split('.').next();
+        if !LanguageManager::languages()
+            .iter()
+            .any(|l| l.installed() && l.code().split('.').next() == prefix)
+        {
+            log::error!("Language {language} is not available");
+            return false.to_string();
+        }
+        *self.language.borrow_mut() = Some(language.to_string());
+        true.to_string()
+    }
+
+    fn set_greeter_language(&self, language: &str) -> String {
+        match LanguageManager::set_greeter_language(language) {
+            Some(language) => {
+                signals::language_changed(&self.webview, &language.localized());
+                true.to_string()
+            }
+            None => {
+                log::error!("Language {language} is not available");
+                false.to_string()
+            }
+        }
+    }
+
+    /// Start session, run as a uwsm-managed unit or directly as chosen by uwsm under greetd
+    fn start_session(&self, session: Option<&str>, uwsm: Option<bool>) -> String {
+        // the language of the greeter is kept, unless one is chosen for the session
+        let language = self
+            .language
+            .borrow()
+            .clone()
+            .or_else(LanguageManager::greeter_language);
+        let layout = LayoutManager::instance().layout();
+        match self.display_manager.as_str() {
+            "lightdm" => {
+                if uwsm.is_some() {
+                    log::warn!("LightDM starts sessions as their desktop files say, ignoring uwsm");
+                }
+                self.lightdm.as_ref().unwrap().start_session_sync(
+                    session.map(str::to_string),
+                    language.as_deref(),
+                    layout,
+                )
+            }
+            "greetd" => match session {
+                Some(session) => self.greetd.as_ref().unwrap().start_session(
+                    session,
+                    uwsm,
+                    language.as_deref(),
+                    layout,
+                ),
+                None => {
+                    log::error!("greetd requires a session to start");
+                    false.to_string()
+                }
+            },
+            dm => unimplemented!("Unsupported display manager: {dm}"),
+        }
+    }
+
     fn layout(&self) -> String {
-        serde_json::to_string(LayoutManager::instance().layout()).unwrap()
+        match LayoutManager::instance().layout() {
+            Some(layout) => serde_json::to_string(&layout.localized()).unwrap(),
+            None => "null".to_string(),
+        }
     }
 
     fn set_layout(&self, layout: &str) -> String {
@@ -162,14 +341,60 @@ impl Greeter {
     }
 
     fn layouts(&self) -> String {
-        serde_json::to_string(LayoutManager::instance().layouts()).unwrap()
+        let layouts: Vec<_> = LayoutManager::instance()
+            .layouts()
+            .iter()
+            .map(Layout::localized)
+            .collect();
+        serde_json::to_string(&layouts).unwrap()
+    }
+
+    fn lock_keys(&self) -> String {
+        serde_json::to_string(&LockKeys::current()).unwrap()
     }
 
     fn sessions(&self) -> String {
-        serde_json::to_string(&SessionManager::sessions()).unwrap()
+        // LightDM only starts sessions of desktop files, one for each file name
+        let lightdm = self.display_manager == "lightdm";
+        let sessions: Vec<_> = SessionManager::sessions()
+            .iter()
+            .filter(|s| !(lightdm && (s.is_custom() || SessionManager::shadowed_in_lightdm(s))))
+            .map(Session::localized)
+            .collect();
+        serde_json::to_string(&sessions).unwrap()
     }
 
     fn users(&self) -> String {
-        serde_json::to_string(UserManager::instance().list_users()).unwrap()
+        if Config::instance().users().hide_user_list() {
+            return "[]".to_string();
+        }
+        serde_json::to_string(&UserManager::instance().list_users()).unwrap()
+    }
+
+    fn hide_users_hint(&self) -> String {
+        (Config::instance().users().hide_user_list()
+            || self.lightdm.as_ref().is_some_and(|l| l.hide_users_hint()))
+        .to_string()
+    }
+
+    fn existing_sessions(&self) -> String {
+        serde_json::to_string(&SessionsManager::sessions()).unwrap()
+    }
+
+    /// Switch to the running session of username, once the user is authenticated
+    fn activate_existing_session(&self, username: &str) -> String {
+        let authenticated_user = match self.display_manager.as_str() {
+            "lightdm" => self.lightdm.as_ref().unwrap().authenticated_user(),
+            "greetd" => self.greetd.as_ref().unwrap().authenticated_user(),
+            dm => unimplemented!("Unsupported display manager: {dm}"),
+        };
+        if authenticated_user.as_deref() != Some(username) {
+            log::error!("{username} must be authenticated to activate the existing session");
+            return false.to_string();
+        }
+        SessionsManager::activate_session(username)
+            .inspect_err(|e| log::error!("Failed to activate session of {username}: {e}"))
+            .is_ok()
+            .to_string()
     }
 }
@@ -6,6 +6,8 @@ use webkit::{
     UserMessage, WebView, gio::Cancellable, glib::variant::ToVariant, prelude::WebViewExt,
 };
 
+use crate::common::{Language, Layout, LockKeys};
+
 #[derive(Debug, Clone, Copy)]
 pub enum PromptType {
     Visible = 0,
@@ -68,6 +70,39 @@ pub(super) fn show_message(webview: &WebView, text: &str, ty: MessageType) {
     webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
 }
 
+pub(super) fn sessions_changed(webview: &WebView) {
+    let parameters = ["sessions_changed", "[]"].to_variant();
+    let message = UserMessage::new("greeter", Some(&parameters));
+    webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+}
+
+pub(super) fn users_changed(webview: &WebView) {
+    let parameters = ["users_changed", "[]"].to_variant();
+    let message = UserMessage::new("greeter", Some(&parameters));
+    webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+}
+
+pub(super) fn language_changed(webview: &WebView, language: &Language) {
+    let language = serde_json::to_string(language).unwrap();
+    let parameters = ["language_changed", &format!("[{language}]")].to_variant();
+    let message = UserMessage::new("greeter", Some(&parameters));
+    webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+}
+
+pub(super) fn layout_changed(webview: &WebView, layout: &Layout) {
+    let layout = serde_json::to_string(layout).unwrap();
+    let parameters = ["layout_changed", &format!("[{layout}]")].to_variant();
+    let message = UserMessage::new("greeter", Some(&parameters));
+    webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+}
+
+pub(super) fn lock_keys_changed(webview: &WebView, lock_keys: &LockKeys) {
+    let lock_keys = serde_json::to_string(lock_keys).unwrap();
+    let parameters = ["lock_keys_changed", &format!("[{lock_keys}]")].to_variant();
+    let message = UserMessage::new("greeter", Some(&parameters));
+    webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+}
+
 pub(super) fn authentication_complete(webview: &WebView) {
     let parameters = ["authentication_complete", "[]"].to_variant();
     let message = UserMessage::new("greeter", Some(&parameters));
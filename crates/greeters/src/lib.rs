@@ -3,6 +3,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later AND LGPL-3.0-or-later
 
 mod common;
+mod config;
 mod greeters;
 
+pub use common::{AVATAR_URI_PREFIX, Avatar, LanguageManager, Screensaver};
+pub use config::Config;
 pub use greeters::Greeter;
@@ -5,14 +5,16 @@
 use gtk::{
     Application, CssProvider,
     gdk::{Display, Monitor},
-    gio::{ActionEntry, File, MenuModel},
+    gio::{self, ActionEntry, File, MemoryInputStream, MenuModel},
+    glib,
     prelude::*,
 };
-use webkit::{CacheModel, WebContext, prelude::WebViewExt};
+use webkit::{CacheModel, URISchemeRequest, WebContext, prelude::*};
 
 use crate::{
     config::Config,
     constants::{GREETER_RESOURCE_PREFIX, WEB_EXTENSIONS_DIR},
+    screensaver::Screensaver,
     service::Dispatcher,
     theme::load_theme_html,
     webview::{primary_user_message_received, secondary_user_message_received, webview_new},
@@ -24,6 +26,11 @@ pub fn on_activate(app: &Application, config: &Config) {
     {
         let webcontext = WebContext::default().expect("default web context does not exist");
         webcontext.set_cache_model(CacheModel::DocumentViewer);
+        webcontext.register_uri_scheme("webkit-greeter", uri_scheme_request);
+        if let Some(security_manager) = webcontext.security_manager() {
+            security_manager.register_uri_scheme_as_secure("webkit-greeter");
+            security_manager.register_uri_scheme_as_cors_enabled("webkit-greeter");
+        }
         let secure_mode = config.secure_mode();
         let detect_theme_error = config.detect_theme_errors();
         let api = greeter_api(&dm);
@@ -86,7 +93,7 @@ pub fn on_activate(app: &Application, config: &Config) {
         setup_window(&primary, app, primary_monitor, debug);
         primary.grab_focus();
 
-        let secondaries = secondary_monitors
+        let secondaries: Vec<_> = secondary_monitors
             .iter()
             .map(|(_, monitor)| {
                 let secondary = webview_new(debug, &secondary_html);
@@ -99,6 +106,15 @@ pub fn on_activate(app: &Application, config: &Config) {
         (primary, secondaries)
     };
 
+    Screensaver::setup(
+        config.screensaver_timeout(),
+        [&primary]
+            .into_iter()
+            .chain(&secondaries)
+            .cloned()
+            .collect(),
+    );
+
     let dispatcher = Dispatcher::new(config.clone(), primary.clone(), secondaries, &dm);
     primary.connect_user_message_received(move |webview, message| {
         primary_user_message_received(webview, message, &dispatcher)
@@ -154,6 +170,32 @@ fn greeter_api(dm: &str) -> String {
     }
 }
 
+// Serve webkit-greeter:// URIs, i.e. user avatars which are not readable by themes directly.
+fn uri_scheme_request(request: &URISchemeRequest) {
+    let uri = request.uri().unwrap_or_default();
+    let avatar = uri
+        .strip_prefix(greeters::AVATAR_URI_PREFIX)
+        .and_then(|username| glib::uri_unescape_string(username, None::<&str>))
+        .and_then(|username| greeters::Avatar::path(&username))
+        .and_then(|path| std::fs::read(&path).ok().map(|data| (path, data)));
+    match avatar {
+        Some((path, data)) => {
+            let (content_type, _) = gio::content_type_guess(Some(&path), data.as_slice());
+            let mime_type = gio::content_type_get_mime_type(&content_type);
+            let length = data.len() as i64;
+            let stream = MemoryInputStream::from_bytes(&glib::Bytes::from_owned(data));
+            request.finish(&stream, length, mime_type.as_deref());
+        }
+        None => {
+            log::warn!("Failed to load {uri}");
+            request.finish_error(&mut glib::Error::new(
+                gio::IOErrorEnum::NotFound,
+                &format!("{uri} not found"),
+            ));
+        }
+    }
+}
+
 // Get current displaymanager managed by systemd.
 fn current_display_manager() -> String {
     match std::process::Command::new("systemctl")
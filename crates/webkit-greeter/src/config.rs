@@ -15,6 +15,8 @@ pub struct Config {
     #[serde(default = "default_themes_dir")]
     themes_dir: String,
     primary_monitor: Option<String>,
+    #[serde(flatten)]
+    greeters: greeters::Config,
 }
 
 pub fn default_themes_dir() -> String {
@@ -33,6 +35,8 @@ impl Config {
         if let Some(theme) = theme {
             config.set_theme(theme);
         }
+        let user_image = config.branding.user_image.clone();
+        config.greeters.set_default_avatar(&user_image);
 
         log::debug!("Configuration loaded");
         config
@@ -46,6 +50,10 @@ impl Config {
         self.greeter.detect_theme_errors
     }
 
+    pub fn screensaver_timeout(&self) -> u32 {
+        self.greeter.screensaver_timeout
+    }
+
     pub fn secure_mode(&self) -> bool {
         self.greeter.secure_mode
     }
@@ -74,6 +82,10 @@ impl Config {
         self.primary_monitor.as_deref()
     }
 
+    pub fn greeters(&self) -> &greeters::Config {
+        &self.greeters
+    }
+
     fn set_debug_mode(&mut self, debug_mode: bool) {
         self.greeter.debug_mode |= debug_mode;
     }
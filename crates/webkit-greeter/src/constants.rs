@@ -4,6 +4,10 @@
 
 pub const APPLICATION_ID: &str = "com.github.zaynchen.webkit-greeter";
 
+pub const GETTEXT_PACKAGE: &str = "webkit-greeter";
+
+pub const LOCALE_DIR: &str = "/usr/share/locale";
+
 pub const DEFAULT_BACKGROUND_IMAGES_DIR: &str = "/usr/share/backgrounds";
 
 pub const DEFAULT_THEME: &str = "litarvan";
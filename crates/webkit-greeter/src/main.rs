@@ -5,6 +5,7 @@
 mod application;
 mod config;
 mod constants;
+mod screensaver;
 mod service;
 mod theme;
 mod webview;
@@ -15,7 +16,7 @@ use gtk::{gio, glib, prelude::*};
 use crate::{
     application::{on_activate, on_startup},
     config::Config,
-    constants::{APPLICATION_ID, WEBKIT_APPLICATION_INFO},
+    constants::{APPLICATION_ID, GETTEXT_PACKAGE, LOCALE_DIR, WEBKIT_APPLICATION_INFO},
     theme::print_themes,
 };
 
@@ -35,6 +36,14 @@ fn main() -> glib::ExitCode {
 
     logger::logger_init(log::LevelFilter::Debug);
 
+    // messages of the greeter follow greeter.set_greeter_language() of themes
+    if let Err(e) = gettextrs::bindtextdomain(GETTEXT_PACKAGE, LOCALE_DIR)
+        .and_then(|_| gettextrs::bind_textdomain_codeset(GETTEXT_PACKAGE, "UTF-8"))
+        .and_then(|_| gettextrs::textdomain(GETTEXT_PACKAGE))
+    {
+        log::warn!("Failed to set up translations: {e}");
+    }
+
     let args = CliArgs::parse();
     let config = Config::new(args.debug_mode(), args.theme());
 
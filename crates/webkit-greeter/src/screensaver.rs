@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::{
+    EventControllerLegacy, PropagationPhase,
+    gdk::EventType,
+    gio::Cancellable,
+    glib::{self, clone, variant::ToVariant},
+    prelude::*,
+};
+use webkit::{UserMessage, WebView, prelude::WebViewExt};
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// Blank the displays after `timeout` of input inactivity on any of the webviews
+pub struct Screensaver {
+    timeout: Duration,
+    webviews: Vec<WebView>,
+    last_activity: Cell<Instant>,
+    active: Cell<bool>,
+}
+
+impl Screensaver {
+    pub fn setup(timeout: u32, webviews: Vec<WebView>) {
+        if timeout == 0 {
+            log::debug!("Screensaver disabled");
+            return;
+        }
+        let screensaver = Rc::new(Self {
+            timeout: Duration::from_secs(timeout.into()),
+            webviews,
+            last_activity: Cell::new(Instant::now()),
+            active: Cell::new(false),
+        });
+
+        screensaver.webviews.iter().for_each(|webview| {
+            let controller = EventControllerLegacy::new();
+            controller.set_propagation_phase(PropagationPhase::Capture);
+            controller.connect_event(clone!(
+                #[strong]
+                screensaver,
+                move |_, event| {
+                    if matches!(
+                        event.event_type(),
+                        EventType::MotionNotify
+                            | EventType::ButtonPress
+                            | EventType::KeyPress
+                            | EventType::Scroll
+                            | EventType::TouchBegin
+                            | EventType::TouchUpdate
+                    ) {
+                        screensaver.activity();
+                    }
+                    glib::Propagation::Proceed
+                }
+            ));
+            webview.add_controller(controller);
+        });
+
+        screensaver.schedule(screensaver.timeout);
+    }
+
+    fn schedule(self: &Rc<Self>, delay: Duration) {
+        glib::timeout_add_local_once(
+            delay,
+            clone!(
+                #[strong(rename_to = screensaver)]
+                self,
+                move || screensaver.check()
+            ),
+        );
+    }
+
+    fn check(self: &Rc<Self>) {
+        let idle = self.last_activity.get().elapsed();
+        if idle >= self.timeout {
+            self.start();
+        } else {
+            self.schedule(self.timeout - idle);
+        }
+    }
+
+    fn activity(self: &Rc<Self>) {
+        self.last_activity.set(Instant::now());
+        if self.active.get() {
+            self.stop();
+            self.schedule(self.timeout);
+        }
+    }
+
+    fn start(&self) {
+        log::debug!("Screensaver started");
+        self.active.set(true);
+        greeters::Screensaver::activate();
+        self.emit("screensaver_start");
+    }
+
+    fn stop(&self) {
+        log::debug!("Screensaver stopped");
+        self.active.set(false);
+        greeters::Screensaver::deactivate();
+        self.emit("screensaver_end");
+    }
+
+    fn emit(&self, signal: &str) {
+        self.webviews.iter().for_each(|webview| {
+            let parameters = [signal, "[]"].to_variant();
+            let message = UserMessage::new("greeter", Some(&parameters));
+            webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+        });
+    }
+}
@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{collections::HashMap, path::Path};
+
+/// Translations of a theme for one language, read from the locale directory of the theme.
+///
+/// For a language like "de_DE.UTF-8@euro", the first existing file of the candidates
+/// "de_DE.UTF-8@euro", "de_DE.UTF-8", "de_DE@euro", "de_DE", "de@euro", "de" is loaded,
+/// where each candidate is looked up as `<candidate>.json`, `<candidate>.mo` and
+/// `<candidate>/LC_MESSAGES/<theme>.mo`.
+pub(super) struct Catalog {
+    language: Option<String>,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub(super) fn empty() -> Self {
+        Self {
+            language: None,
+            messages: HashMap::new(),
+        }
+    }
+
+    pub(super) fn load(locale_dir: &Path, theme: &str, language: &str) -> Self {
+        let messages = candidates(language)
+            .iter()
+            .flat_map(|c| {
+                [
+                    locale_dir.join(format!("{c}.json")),
+                    locale_dir.join(format!("{c}.mo")),
+                    locale_dir
+                        .join(c)
+                        .join("LC_MESSAGES")
+                        .join(format!("{theme}.mo")),
+                ]
+            })
+            .find(|path| path.is_file())
+            .and_then(|path| {
+                let messages = read_catalog(&path);
+                if messages.is_none() {
+                    log::warn!("Failed to read translation catalog {path:?}");
+                }
+                messages
+            })
+            .unwrap_or_default();
+        Self {
+            language: Some(language.to_string()),
+            messages,
+        }
+    }
+
+    pub(super) fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub(super) fn translate<'a>(&'a self, msgid: &'a str) -> &'a str {
+        self.messages
+            .get(msgid)
+            .map(String::as_str)
+            .unwrap_or(msgid)
+    }
+}
+
+/// Catalog names for a locale code, from the most to the least specific one
+fn candidates(language: &str) -> Vec<String> {
+    let (name, modifier) = match language.split_once('@') {
+        Some((name, modifier)) => (name, Some(modifier)),
+        None => (language, None),
+    };
+    let (territory, codeset) = match name.split_once('.') {
+        Some((territory, codeset)) => (territory, Some(codeset)),
+        None => (name, None),
+    };
+    let lang = territory.split('_').next().unwrap_or(territory);
+
+    let mut candidates = vec![];
+    for base in [Some(name), codeset.map(|_| territory), Some(lang)]
+        .into_iter()
+        .flatten()
+    {
+        if let Some(modifier) = modifier {
+            candidates.push(format!("{base}@{modifier}"));
+        }
+        candidates.push(base.to_string());
+    }
+    candidates.dedup();
+    candidates
+}
+
+fn read_catalog(path: &Path) -> Option<HashMap<String, String>> {
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    } else {
+        read_mo(&std::fs::read(path).ok()?)
+    }
+}
+
+/// Messages of a compiled gettext catalog, plural forms keep their singular translation
+fn read_mo(data: &[u8]) -> Option<HashMap<String, String>> {
+    let magic = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+    let read_u32: fn(&[u8]) -> u32 = match magic {
+        0x950412de => |b| u32::from_le_bytes(b.try_into().unwrap()),
+        0xde120495 => |b| u32::from_be_bytes(b.try_into().unwrap()),
+        _ => return None,
+    };
+    let word = |offset: usize| data.get(offset..offset + 4).map(read_u32);
+    let string = |table: usize, index: usize| {
+        let entry = table + index * 8;
+        let len = word(entry)? as usize;
+        let offset = word(entry + 4)? as usize;
+        data.get(offset..offset + len)
+            .and_then(|s| std::str::from_utf8(s).ok())
+    };
+
+    let count = word(8)? as usize;
+    let originals = word(12)? as usize;
+    let translations = word(16)? as usize;
+    // count comes from the file, entries are only allocated once they are read
+    let mut messages = HashMap::new();
+    for i in 0..count {
+        let msgid = string(originals, i)?;
+        let msgstr = string(translations, i)?;
+        // The header has an empty msgid, messages with a msgctxt cannot be looked up by msgid
+        if msgid.is_empty() || msgid.contains('\x04') {
+            continue;
+        }
+        let msgid = msgid.split('\0').next().unwrap_or(msgid);
+        let msgstr = msgstr.split('\0').next().unwrap_or(msgstr);
+        if !msgstr.is_empty() {
+            messages.insert(msgid.to_string(), msgstr.to_string());
+        }
+    }
+    Some(messages)
+}
+
+#[cfg(test)]
+mod tests;
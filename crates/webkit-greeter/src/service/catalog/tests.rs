@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2026 ZaynChen <zaynchen@qq.com>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{candidates, read_mo};
+
+/// Compiled catalog of messages, like msgfmt writes it without a hash table
+fn mo(messages: &[(&str, &str)], big_endian: bool) -> Vec<u8> {
+    let word = |n: usize| {
+        let n = n as u32;
+        if big_endian {
+            n.to_be_bytes()
+        } else {
+            n.to_le_bytes()
+        }
+    };
+    let count = messages.len();
+    let originals = 28;
+    let translations = originals + count * 8;
+    let mut strings = translations + count * 8;
+
+    let mut tables = vec![];
+    let mut data = vec![];
+    for column in [0, 1] {
+        for message in messages {
+            let s = if column == 0 { message.0 } else { message.1 };
+            tables.extend(word(s.len()));
+            tables.extend(word(strings));
+            data.extend(s.as_bytes());
+            data.push(0);
+            strings += s.len() + 1;
+        }
+    }
+
+    let mut mo = vec![];
+    mo.extend(word(0x950412de));
+    mo.extend(word(0));
+    mo.extend(word(count));
+    mo.extend(word(originals));
+    mo.extend(word(translations));
+    mo.extend(word(0));
+    mo.extend(word(strings));
+    mo.extend(tables);
+    mo.extend(data);
+    mo
+}
+
+#[test]
+fn candidates_from_most_to_least_specific() {
+    assert_eq!(
+        candidates("de_DE.UTF-8@euro"),
+        [
+            "de_DE.UTF-8@euro",
+            "de_DE.UTF-8",
+            "de_DE@euro",
+            "de_DE",
+            "de@euro",
+            "de"
+        ]
+    );
+    assert_eq!(candidates("pt_BR"), ["pt_BR", "pt"]);
+    assert_eq!(candidates("de"), ["de"]);
+}
+
+#[test]
+fn read_mo_in_both_byte_orders() {
+    let messages = [
+        ("", "Content-Type: text/plain; charset=UTF-8\n"),
+        ("Log in", "Anmelden"),
+        ("Password", "Passwort"),
+    ];
+    for big_endian in [false, true] {
+        let catalog = read_mo(&mo(&messages, big_endian)).unwrap();
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(catalog["Log in"], "Anmelden");
+        assert_eq!(catalog["Password"], "Passwort");
+    }
+}
+
+#[test]
+fn read_mo_skips_contexts_and_keeps_singular_of_plurals() {
+    let catalog = read_mo(&mo(
+        &[
+            ("menu\x04Open", "Öffnen"),
+            ("%d user\0%d users", "%d Benutzer\0%d Benutzer"),
+            ("Untranslated", ""),
+        ],
+        false,
+    ))
+    .unwrap();
+    assert_eq!(catalog.len(), 1);
+    assert_eq!(catalog["%d user"], "%d Benutzer");
+}
+
+#[test]
+fn read_mo_rejects_malformed_catalogs() {
+    assert!(read_mo(b"").is_none());
+    assert!(read_mo(&[0; 28]).is_none());
+
+    // a huge message count must not be trusted for allocation
+    let mut catalog = mo(&[("Log in", "Anmelden")], false);
+    catalog[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(read_mo(&catalog).is_none());
+
+    // a string that points past the end of the file
+    let mut catalog = mo(&[("Log in", "Anmelden")], false);
+    catalog[28..32].copy_from_slice(&1000u32.to_le_bytes());
+    assert!(read_mo(&catalog).is_none());
+}
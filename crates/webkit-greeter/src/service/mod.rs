@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod catalog;
 mod greeter_comm;
 mod greeter_config;
 mod theme_utils;
@@ -13,7 +14,7 @@ mod dispatcher {
 
     use greeters::Greeter;
 
-    use crate::config::Config;
+    use crate::{config::Config, theme::theme_locale_dir};
 
     use super::{
         greeter_comm::GreeterComm, greeter_config::GreeterConfig, theme_utils::ThemeUtils,
@@ -38,8 +39,12 @@ mod dispatcher {
                 config.background_images_dir().to_string(),
             ];
             Self {
-                theme_utils: ThemeUtils::new(&allowed_dirs, config.theme()),
-                greeter: Greeter::new(&primary, display_manager),
+                theme_utils: ThemeUtils::new(
+                    &allowed_dirs,
+                    config.theme(),
+                    theme_locale_dir(config.themes_dir(), config.theme()),
+                ),
+                greeter: Greeter::new(&primary, display_manager, config.greeters().clone()),
                 greeter_config: GreeterConfig::new(config),
                 greeter_comm: GreeterComm::new(primary, secondaries),
             }
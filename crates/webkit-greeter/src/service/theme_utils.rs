@@ -4,30 +4,68 @@
 
 use gtk::glib::{self, tmp_dir, variant::ToVariant};
 
+use std::{cell::RefCell, path::PathBuf};
+
+use greeters::LanguageManager;
+
+use super::catalog::Catalog;
+
 pub(super) struct ThemeUtils {
     allowed_dirs: Vec<String>,
+    theme: String,
+    locale_dir: Option<PathBuf>,
+    catalog: RefCell<Catalog>,
 }
 
 impl ThemeUtils {
-    pub(super) fn new(allowed_dirs: &[String], theme: &str) -> Self {
+    pub(super) fn new(allowed_dirs: &[String], theme: &str, locale_dir: Option<PathBuf>) -> Self {
         let mut allowed_dirs: Vec<String> = allowed_dirs.iter().map(|s| s.to_string()).collect();
         if let Ok(path) = std::fs::canonicalize(theme) {
             let theme_dir = path.with_file_name("");
             allowed_dirs.push(theme_dir.to_string_lossy().to_string());
         }
         allowed_dirs.push(tmp_dir().to_string_lossy().to_string());
-        Self { allowed_dirs }
+        Self {
+            allowed_dirs,
+            theme: theme.to_string(),
+            locale_dir,
+            catalog: RefCell::new(Catalog::empty()),
+        }
     }
 
     pub(super) fn handle(&self, method: &str, json_args: &str) -> glib::Variant {
-        let json_result = if "dirlist" == method && json_args != "[]" {
-            self.dirlist(json_args)
-        } else {
-            "undefined".to_string()
+        let json_result = match method {
+            "dirlist" if json_args != "[]" => self.dirlist(json_args),
+            "translate" if json_args != "[]" => self.translate(json_args),
+            _ => "undefined".to_string(),
         };
         json_result.to_variant()
     }
 
+    /// Translation of msgid in the catalog of the greeter language, msgid itself if there is none.
+    /// The catalog is reloaded whenever the greeter language has changed since the last call.
+    fn translate(&self, json_args: &str) -> String {
+        let msgid = if let Ok(serde_json::Value::Array(args)) = serde_json::from_str(json_args)
+            && let Some(serde_json::Value::String(msgid)) = args.first()
+        {
+            msgid.clone()
+        } else {
+            return "undefined".to_string();
+        };
+        let Some(locale_dir) = &self.locale_dir else {
+            return serde_json::to_string(&msgid).unwrap();
+        };
+
+        let language = LanguageManager::greeter_language()
+            .or_else(|| std::env::var("LANG").ok())
+            .unwrap_or_default();
+        if self.catalog.borrow().language() != Some(language.as_str()) {
+            self.catalog
+                .replace(Catalog::load(locale_dir, &self.theme, &language));
+        }
+        serde_json::to_string(self.catalog.borrow().translate(&msgid)).unwrap()
+    }
+
     fn dirlist(&self, json_args: &str) -> String {
         let args: serde_json::Value =
             serde_json::from_str(json_args).expect("args is not a JSON string");
@@ -70,6 +70,34 @@ pub fn load_theme_html(themes_dir: &str, theme: &str) -> (String, String) {
     }
 }
 
+/// Directory of the translation catalogs, set by `locale_dir` in index.yml of the theme
+pub fn theme_locale_dir(themes_dir: &str, theme: &str) -> Option<PathBuf> {
+    let theme_dir = absolute(["/", themes_dir, theme].iter().collect::<PathBuf>()).ok()?;
+    let content = std::fs::read_to_string(theme_dir.join("index.yml")).ok()?;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| l.starts_with("locale_dir"))
+        .filter_map(|s| s.split_once(':'))
+        .map(|(_, v)| v.trim().trim_matches(['\"', '\'']))
+        .find(|v| !v.is_empty())
+        .and_then(|v| {
+            // the catalogs must belong to the theme, not to an absolute or ".." path
+            let path = theme_dir.join(v);
+            match (path.canonicalize(), theme_dir.canonicalize()) {
+                (Ok(locale_dir), Ok(theme_dir))
+                    if locale_dir.is_dir() && locale_dir.starts_with(&theme_dir) =>
+                {
+                    Some(locale_dir)
+                }
+                _ => {
+                    log::warn!("Theme locale directory {path:?} is not a directory of the theme");
+                    None
+                }
+            }
+        })
+}
+
 fn load_theme_config(theme_dir: &Path) -> (String, Option<String>) {
     match std::fs::read_to_string(theme_dir.join("index.yml")) {
         Ok(content) => {
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use gettextrs::gettext;
 use gtk::{AlertDialog, ApplicationWindow, gdk, gio::Cancellable};
 use webkit::{HardwareAccelerationPolicy, Settings, UserMessage, WebView, prelude::*};
 
@@ -87,9 +88,15 @@ pub fn show_console_error_prompt(message: UserMessage, dispatcher: &Dispatcher)
         .expect("webview.root is not a ApplicationWindow");
     let themes_dir = dispatcher.themes_dir();
     AlertDialog::builder()
-        .message("An error ocurred. Change to default theme? (litarvan)")
+        .message(gettext(
+            "An error ocurred. Change to default theme? (litarvan)",
+        ))
         .detail(format!("{source_id} {line}: {msg}"))
-        .buttons(["Cancel", "Use default theme", "Reload theme"])
+        .buttons([
+            gettext("Cancel"),
+            gettext("Use default theme"),
+            gettext("Reload theme"),
+        ])
         .build()
         .choose(Some(window), Some(&Cancellable::new()), move |res| {
             if let Ok(stop_prompts) = res.map(|response| match response {
@@ -15,6 +15,11 @@ _pkgname="webkit-greeter"
 build() {
   cargo build --release --locked
 
+  for _lang in $(cat po/LINGUAS); do
+    mkdir -p "target/locale/$_lang/LC_MESSAGES"
+    msgfmt -o "target/locale/$_lang/LC_MESSAGES/$_pkgname.mo" "po/$_lang.po"
+  done
+
   CURR_DIR=$(pwd)
   cd themes/litarvan
   ./build.sh
@@ -33,6 +38,11 @@ package() {
 
   sudo install -Dm0644 examples/* -t "/usr/share/doc/$_pkgname/examples"
 
+  for _lang in $(cat po/LINGUAS); do
+    sudo install -Dm0644 "target/locale/$_lang/LC_MESSAGES/$_pkgname.mo" \
+      "/usr/share/locale/$_lang/LC_MESSAGES/$_pkgname.mo"
+  done
+
   CURR_DIR=$(pwd)
   cd themes/litarvan
   _themepkg="*-$(cat version).tar.gz"
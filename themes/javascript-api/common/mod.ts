@@ -67,6 +67,14 @@ class ThemeUtils {
       return [];
     }
   }
+  translate(msgid: string): string {
+    if ("string" !== typeof msgid) {
+      console.error("[ERROR] theme_utils.translate(): msgid must be a string!");
+      return msgid;
+    }
+    return (sendRequest("theme_utils", "translate", [msgid]) as string) ??
+      msgid;
+  }
 }
 
 globalThis.greeter_comm = new GreeterComm();
@@ -56,6 +56,15 @@ var ThemeUtils = class {
       return [];
     }
   }
+  translate(msgid) {
+    if ("string" !== typeof msgid) {
+      console.error("[ERROR] theme_utils.translate(): msgid must be a string!");
+      return msgid;
+    }
+    return sendRequest("theme_utils", "translate", [
+      msgid
+    ]) ?? msgid;
+  }
 };
 globalThis.greeter_comm = new GreeterComm();
 globalThis.greeter_config = new GreeterConfig();
@@ -81,10 +90,24 @@ var Greeter = class {
   show_prompt;
   show_message;
   authentication_complete;
+  screensaver_start;
+  screensaver_end;
+  users_changed;
+  sessions_changed;
+  language_changed;
+  layout_changed;
+  lock_keys_changed;
   constructor() {
     this.show_prompt = new Signal();
     this.show_message = new Signal();
     this.authentication_complete = new Signal();
+    this.screensaver_start = new Signal();
+    this.screensaver_end = new Signal();
+    this.users_changed = new Signal();
+    this.sessions_changed = new Signal();
+    this.language_changed = new Signal();
+    this.layout_changed = new Signal();
+    this.lock_keys_changed = new Signal();
   }
   #sendRequest(method, args) {
     return sendRequest("greeter", method, args);
@@ -127,12 +150,24 @@ var Greeter = class {
   get layouts() {
     return this.#sendRequest("layouts");
   }
+  get lock_keys() {
+    return this.#sendRequest("lock_keys");
+  }
   get sessions() {
     return this.#sendRequest("sessions");
   }
   get users() {
     return this.#sendRequest("users");
   }
+  get hide_users_hint() {
+    return this.#sendRequest("hide_users_hint");
+  }
+  get first_boot() {
+    return this.#sendRequest("first_boot");
+  }
+  get existing_sessions() {
+    return this.#sendRequest("existing_sessions");
+  }
   hibernate() {
     return this.#sendRequest("hibernate");
   }
@@ -158,9 +193,32 @@ var Greeter = class {
   cancel_authentication() {
     return this.#sendRequest("cancel_authentication");
   }
-  start_session(session) {
+  create_first_user(username, display_name, password) {
+    return this.#sendRequest("create_first_user", [
+      username,
+      display_name,
+      password
+    ]);
+  }
+  set_greeter_language(language) {
+    return this.#sendRequest("set_greeter_language", [
+      language
+    ]);
+  }
+  set_language(language) {
+    return this.#sendRequest("set_language", [
+      language
+    ]);
+  }
+  start_session(session, uwsm) {
     return this.#sendRequest("start_session", [
-      session
+      session,
+      uwsm
+    ]);
+  }
+  activate_existing_session(username) {
+    return this.#sendRequest("activate_existing_session", [
+      username
     ]);
   }
 };
@@ -34,6 +34,8 @@ assertEquals(await theme_utils.dirlist("/usr/share/backgrounds", false), [
   "/usr/share/backgrounds/archlinux/archbtw.png",
   "/usr/share/backgrounds/archlinux/awesome.png",
 ]);
+assertEquals(theme_utils.translate("Log in"), "Anmelden");
+assertEquals(theme_utils.translate("Shut down"), "Shut down");
 assertEquals(
   greeter_comm.broadcast({
     "type": "change-background",
@@ -43,25 +45,37 @@ assertEquals(
 );
 
 assertEquals(greeter.language, {
-  code: "en_US.utf8",
+  code: "en_US.UTF-8",
   name: "American English",
   territory: "United States",
+  installed: true,
+  native_name: "English",
+  native_territory: "United States",
 });
 assertEquals(greeter.languages, [
   {
     name: "American English",
-    code: "en_US.utf8",
+    code: "en_US.UTF-8",
     territory: "United States",
+    installed: true,
+    native_name: "English",
+    native_territory: "United States",
   },
   {
     name: "Français",
-    code: "fr_FR.utf8",
+    code: "fr_FR.UTF-8",
     territory: "",
+    installed: true,
+    native_name: "français",
+    native_territory: null,
   },
   {
     name: "中文",
-    code: "zh_CN.utf8",
+    code: "zh_CN.UTF-8",
     territory: "中国",
+    installed: true,
+    native_name: "中文",
+    native_territory: "中国",
   },
 ]);
 assertEquals(greeter.layout, {
@@ -76,6 +90,7 @@ assertEquals(greeter.layouts, [
     short_description: "en",
   },
 ]);
+assertEquals(greeter.lock_keys, { caps_lock: false, num_lock: true });
 assertEquals(greeter.sessions, [
   { name: "KDE 5", key: "plasma-shell", type: "", comment: "" },
   { name: "Gnome 3", key: "gnome-shell", type: "", comment: "" },
@@ -108,6 +123,22 @@ assertEquals(greeter.users, [
     home_directory: "",
   },
 ]);
+assertFalse(greeter.hide_users_hint);
+assertFalse(greeter.first_boot);
+assertEquals(greeter.existing_sessions, [
+  {
+    id: "2",
+    username: "zaync",
+    uid: 1000,
+    seat: "seat0",
+    vt: 2,
+    tty: "tty2",
+    type: "wayland",
+    active: false,
+    idle: true,
+    idle_since: 1760000000000000,
+  },
+]);
 assert(greeter.can_hibernate);
 assert(greeter.can_restart);
 assert(greeter.can_shutdown);
@@ -124,3 +155,7 @@ assert(greeter.cancel_authentication());
 assert(greeter.authenticate("test"));
 assert(greeter.respond("test"));
 assert(greeter.start_session("hyprland"));
+assert(greeter.start_session("hyprland", true));
+assert(greeter.activate_existing_session("zaync"));
+assert(greeter.set_language("zh_CN.UTF-8"));
+assert(greeter.set_greeter_language("zh_CN.UTF-8"));
@@ -6,6 +6,8 @@ import type {
   GreeterRequestMethod,
   Language,
   Layout,
+  LockKeys,
+  LoginSession,
   Session,
   User,
 } from "../types.d.ts";
@@ -17,10 +19,24 @@ class Greeter {
   show_prompt;
   show_message;
   authentication_complete;
+  screensaver_start;
+  screensaver_end;
+  users_changed;
+  sessions_changed;
+  language_changed;
+  layout_changed;
+  lock_keys_changed;
   constructor() {
     this.show_prompt = new Signal();
     this.show_message = new Signal();
     this.authentication_complete = new Signal();
+    this.screensaver_start = new Signal();
+    this.screensaver_end = new Signal();
+    this.users_changed = new Signal();
+    this.sessions_changed = new Signal();
+    this.language_changed = new Signal();
+    this.layout_changed = new Signal();
+    this.lock_keys_changed = new Signal();
   }
   #sendRequest(
     method: GreeterRequestMethod["greeter"],
@@ -55,8 +71,8 @@ class Greeter {
   get languages(): Language[] {
     return this.#sendRequest("languages") as Language[];
   }
-  get layout(): Layout {
-    return this.#sendRequest("layout") as Layout;
+  get layout(): Layout | null {
+    return this.#sendRequest("layout") as Layout | null;
   }
   set layout(layout: string) {
     this.#sendRequest("layout", [layout]);
@@ -64,12 +80,24 @@ class Greeter {
   get layouts(): Layout[] {
     return this.#sendRequest("layouts") as Layout[];
   }
+  get lock_keys(): LockKeys {
+    return this.#sendRequest("lock_keys") as LockKeys;
+  }
   get sessions(): Session[] {
     return this.#sendRequest("sessions") as Session[];
   }
   get users(): User[] {
     return this.#sendRequest("users") as User[];
   }
+  get hide_users_hint(): boolean {
+    return this.#sendRequest("hide_users_hint") as boolean;
+  }
+  get first_boot(): boolean {
+    return this.#sendRequest("first_boot") as boolean;
+  }
+  get existing_sessions(): LoginSession[] {
+    return this.#sendRequest("existing_sessions") as LoginSession[];
+  }
   hibernate(): boolean {
     return this.#sendRequest("hibernate") as boolean;
   }
@@ -91,8 +119,30 @@ class Greeter {
   cancel_authentication(): boolean {
     return this.#sendRequest("cancel_authentication") as boolean;
   }
-  start_session(session: string): boolean {
-    return this.#sendRequest("start_session", [session]) as boolean;
+  create_first_user(
+    username: string,
+    display_name: string,
+    password: string,
+  ): boolean {
+    return this.#sendRequest("create_first_user", [
+      username,
+      display_name,
+      password,
+    ]) as boolean;
+  }
+  set_greeter_language(language: string): boolean {
+    return this.#sendRequest("set_greeter_language", [language]) as boolean;
+  }
+  set_language(language: string): boolean {
+    return this.#sendRequest("set_language", [language]) as boolean;
+  }
+  start_session(session: string, uwsm?: boolean): boolean {
+    return this.#sendRequest("start_session", [session, uwsm]) as boolean;
+  }
+  activate_existing_session(username: string): boolean {
+    return this.#sendRequest("activate_existing_session", [
+      username,
+    ]) as boolean;
   }
 }
 
@@ -56,6 +56,15 @@ var ThemeUtils = class {
       return [];
     }
   }
+  translate(msgid) {
+    if ("string" !== typeof msgid) {
+      console.error("[ERROR] theme_utils.translate(): msgid must be a string!");
+      return msgid;
+    }
+    return sendRequest("theme_utils", "translate", [
+      msgid
+    ]) ?? msgid;
+  }
 };
 globalThis.greeter_comm = new GreeterComm();
 globalThis.greeter_config = new GreeterConfig();
@@ -82,11 +91,25 @@ var Greeter = class {
   show_message;
   authentication_complete;
   autologin_timer_expired;
+  screensaver_start;
+  screensaver_end;
+  users_changed;
+  sessions_changed;
+  language_changed;
+  layout_changed;
+  lock_keys_changed;
   constructor() {
     this.show_prompt = new Signal();
     this.show_message = new Signal();
     this.authentication_complete = new Signal();
     this.autologin_timer_expired = new Signal();
+    this.screensaver_start = new Signal();
+    this.screensaver_end = new Signal();
+    this.users_changed = new Signal();
+    this.sessions_changed = new Signal();
+    this.language_changed = new Signal();
+    this.layout_changed = new Signal();
+    this.lock_keys_changed = new Signal();
   }
   #sendRequest(method, args) {
     return sendRequest("greeter", method, args);
@@ -209,6 +232,12 @@ var Greeter = class {
   get layouts() {
     return this.#sendRequest("layouts");
   }
+  /**
+   * The current state of the Caps Lock and Num Lock keys.
+   */
+  get lock_keys() {
+    return this.#sendRequest("lock_keys");
+  }
   /**
    * Whether or not the greeter was started as a lock screen.
    */
@@ -260,6 +289,19 @@ var Greeter = class {
   get users() {
     return this.#sendRequest("users");
   }
+  /**
+   * Whether no users exist yet and the first administrator account
+   * should be created with {@link create_first_user}.
+   */
+  get first_boot() {
+    return this.#sendRequest("first_boot");
+  }
+  /**
+   * Graphical sessions of users that are already logged in.
+   */
+  get existing_sessions() {
+    return this.#sendRequest("existing_sessions");
+  }
   get shared_data_directory() {
     return this.#sendRequest("shared_data_directory");
   }
@@ -272,6 +314,31 @@ var Greeter = class {
       username
     ]);
   }
+  /**
+   * Switch to the running graphical session of a user instead of starting a new one.
+   * The user must be authenticated first.
+   * @arg {string} username The user whose session should be activated.
+   * @returns {boolean} {@link true} if the session was activated, otherwise {@link false}
+   */
+  activate_existing_session(username) {
+    return this.#sendRequest("activate_existing_session", [
+      username
+    ]);
+  }
+  /**
+   * Create the first administrator account, only possible while {@link first_boot} is {@link true}.
+   * @arg {string} username The username of the new account.
+   * @arg {string} display_name The real name of the new account.
+   * @arg {string} password The password of the new account.
+   * @returns {boolean} {@link true} if the account was created, otherwise {@link false}
+   */
+  create_first_user(username, display_name, password) {
+    return this.#sendRequest("create_first_user", [
+      username,
+      display_name,
+      password
+    ]);
+  }
   /**
    * Starts the authentication procedure for the guest user.
    */
@@ -312,6 +379,18 @@ var Greeter = class {
   restart() {
     return this.#sendRequest("restart");
   }
+  /**
+   * Switch the language of the greeter itself, the sessions, languages and layouts
+   * are localized into it afterwards and {@link language_changed} is emitted.
+   * It is also used for the session unless {@link set_language} is called.
+   * @arg {string} language The language in the form of a locale specification (e.g. 'de_DE.UTF-8')
+   * @returns {boolean} {@link true} if successful, otherwise {@link false}
+   */
+  set_greeter_language(language) {
+    return this.#sendRequest("set_greeter_language", [
+      language
+    ]);
+  }
   /**
    * Set the language for the currently authenticated user.
    * @arg {string} language The language in the form of a locale specification (e.g. 'de_DE.UTF-8')
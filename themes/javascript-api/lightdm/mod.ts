@@ -7,6 +7,8 @@ import type {
   GreeterRequestMethod,
   Language,
   Layout,
+  LockKeys,
+  LoginSession,
   Session,
   User,
 } from "../types.d.ts";
@@ -19,11 +21,25 @@ class Greeter {
   show_message;
   authentication_complete;
   autologin_timer_expired;
+  screensaver_start;
+  screensaver_end;
+  users_changed;
+  sessions_changed;
+  language_changed;
+  layout_changed;
+  lock_keys_changed;
   constructor() {
     this.show_prompt = new Signal();
     this.show_message = new Signal();
     this.authentication_complete = new Signal();
     this.autologin_timer_expired = new Signal();
+    this.screensaver_start = new Signal();
+    this.screensaver_end = new Signal();
+    this.users_changed = new Signal();
+    this.sessions_changed = new Signal();
+    this.language_changed = new Signal();
+    this.layout_changed = new Signal();
+    this.lock_keys_changed = new Signal();
   }
   #sendRequest(
     method: GreeterRequestMethod["greeter"],
@@ -149,8 +165,8 @@ class Greeter {
   /**
    * The currently active layout for the selected user.
    */
-  get layout(): Layout {
-    return this.#sendRequest("layout") as Layout;
+  get layout(): Layout | null {
+    return this.#sendRequest("layout") as Layout | null;
   }
 
   /**
@@ -167,6 +183,13 @@ class Greeter {
     return this.#sendRequest("layouts") as Layout[];
   }
 
+  /**
+   * The current state of the Caps Lock and Num Lock keys.
+   */
+  get lock_keys(): LockKeys {
+    return this.#sendRequest("lock_keys") as LockKeys;
+  }
+
   /**
    * Whether or not the greeter was started as a lock screen.
    */
@@ -226,6 +249,21 @@ class Greeter {
     return this.#sendRequest("users") as User[];
   }
 
+  /**
+   * Whether no users exist yet and the first administrator account
+   * should be created with {@link create_first_user}.
+   */
+  get first_boot(): boolean {
+    return this.#sendRequest("first_boot") as boolean;
+  }
+
+  /**
+   * Graphical sessions of users that are already logged in.
+   */
+  get existing_sessions(): LoginSession[] {
+    return this.#sendRequest("existing_sessions") as LoginSession[];
+  }
+
   get shared_data_directory() {
     return this.#sendRequest("shared_data_directory");
   }
@@ -238,6 +276,37 @@ class Greeter {
     return this.#sendRequest("authenticate", [username]) as boolean;
   }
 
+  /**
+   * Switch to the running graphical session of a user instead of starting a new one.
+   * The user must be authenticated first.
+   * @arg {string} username The user whose session should be activated.
+   * @returns {boolean} {@link true} if the session was activated, otherwise {@link false}
+   */
+  activate_existing_session(username: string): boolean {
+    return this.#sendRequest("activate_existing_session", [
+      username,
+    ]) as boolean;
+  }
+
+  /**
+   * Create the first administrator account, only possible while {@link first_boot} is {@link true}.
+   * @arg {string} username The username of the new account.
+   * @arg {string} display_name The real name of the new account.
+   * @arg {string} password The password of the new account.
+   * @returns {boolean} {@link true} if the account was created, otherwise {@link false}
+   */
+  create_first_user(
+    username: string,
+    display_name: string,
+    password: string,
+  ): boolean {
+    return this.#sendRequest("create_first_user", [
+      username,
+      display_name,
+      password,
+    ]) as boolean;
+  }
+
   /**
    * Starts the authentication procedure for the guest user.
    */
@@ -282,6 +351,17 @@ class Greeter {
     return this.#sendRequest("restart") as boolean;
   }
 
+  /**
+   * Switch the language of the greeter itself, the sessions, languages and layouts
+   * are localized into it afterwards and {@link language_changed} is emitted.
+   * It is also used for the session unless {@link set_language} is called.
+   * @arg {string} language The language in the form of a locale specification (e.g. 'de_DE.UTF-8')
+   * @returns {boolean} {@link true} if successful, otherwise {@link false}
+   */
+  set_greeter_language(language: string): boolean {
+    return this.#sendRequest("set_greeter_language", [language]) as boolean;
+  }
+
   /**
    * Set the language for the currently authenticated user.
    * @arg {string} language The language in the form of a locale specification (e.g. 'de_DE.UTF-8')
@@ -42,26 +42,38 @@ function greeterHandler(
         return true;
       case "language":
         return {
-          code: "en_US.utf8",
+          code: "en_US.UTF-8",
           name: "American English",
           territory: "United States",
+          installed: true,
+          native_name: "English",
+          native_territory: "United States",
         };
       case "languages":
         return [
           {
             name: "American English",
-            code: "en_US.utf8",
+            code: "en_US.UTF-8",
             territory: "United States",
+            installed: true,
+            native_name: "English",
+            native_territory: "United States",
           },
           {
             name: "Français",
-            code: "fr_FR.utf8",
+            code: "fr_FR.UTF-8",
             territory: "",
+            installed: true,
+            native_name: "français",
+            native_territory: null,
           },
           {
             name: "中文",
-            code: "zh_CN.utf8",
+            code: "zh_CN.UTF-8",
             territory: "中国",
+            installed: true,
+            native_name: "中文",
+            native_territory: "中国",
           },
         ];
       case "layout":
@@ -78,6 +90,8 @@ function greeterHandler(
             short_description: "en",
           },
         ];
+      case "lock_keys":
+        return { caps_lock: false, num_lock: true };
       case "sessions":
         return [
           { name: "KDE 5", key: "plasma-shell", type: "", comment: "" },
@@ -112,6 +126,25 @@ function greeterHandler(
             home_directory: "",
           },
         ];
+      case "hide_users_hint":
+        return false;
+      case "first_boot":
+        return false;
+      case "existing_sessions":
+        return [
+          {
+            id: "2",
+            username: "zaync",
+            uid: 1000,
+            seat: "seat0",
+            vt: 2,
+            tty: "tty2",
+            type: "wayland",
+            active: false,
+            idle: true,
+            idle_since: 1760000000000000,
+          },
+        ];
       case "authentication_user":
         return null;
       case "in_authentication":
@@ -134,6 +167,12 @@ function greeterHandler(
         return true;
       case "start_session":
         return true;
+      case "activate_existing_session":
+        return true;
+      case "set_language":
+        return true;
+      case "set_greeter_language":
+        return true;
       default:
         console.log(`unimplement method "${method}(${param})"`);
         return undefined;
@@ -196,6 +235,11 @@ function themeUtilsHandler(
       ];
     }
   }
+  if (method === "translate") {
+    const [msgid]: [string] = JSON.parse(param);
+    const messages: Record<string, string> = { "Log in": "Anmelden" };
+    return messages[msgid] ?? msgid;
+  }
   console.log(`unimplement method "${method}(${param})"`);
   return undefined;
 }
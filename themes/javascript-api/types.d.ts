@@ -6,6 +6,9 @@ type Language = {
   code: string;
   name: string;
   territory: string;
+  installed: boolean; // false if the locale is only available to be generated
+  native_name: string | null; // name of the language in itself, if installed
+  native_territory: string | null; // name of the territory in the language
 };
 
 type Layout = {
@@ -14,11 +17,42 @@ type Layout = {
   short_description: string | null;
 };
 
+type LockKeys = {
+  caps_lock: boolean;
+  num_lock: boolean;
+};
+
 type Session = {
   key: string;
   name: string;
   type: string;
   comment: string;
+  names: { [locale: string]: string }; // Name[xx] of the desktop file
+  comments: { [locale: string]: string }; // Comment[xx] of the desktop file
+  desktop_names: string[]; // value of XDG_CURRENT_DESKTOP
+  icon: string | null; // path of the icon file
+  try_exec: string | null; // path of the TryExec program
+  extensions: { [key: string]: string }; // X-GDM-* and X-LightDM-* keys
+  uwsm: boolean; // Exec starts the compositor through `uwsm start`
+  uwsm_available: boolean; // can be started either through uwsm or directly
+};
+
+type LoginSession = {
+  id: string;
+  username: string;
+  uid: number;
+  seat: string;
+  vt: number | null;
+  tty: string;
+  type: string;
+  active: boolean;
+  idle: boolean;
+  idle_since: number;
+};
+
+type LoginRecord = {
+  login_time: number;
+  logout_time: number; // 0 if still logged in
 };
 
 type User = {
@@ -28,6 +62,14 @@ type User = {
   language: string;
   session: string;
   username: string;
+  locked: boolean;
+  account_type: "standard" | "administrator";
+  password_hint: string | null;
+  password_mode: "regular" | "set_at_login" | "none";
+  login_time: number | null; // seconds since the epoch
+  login_history: LoginRecord[];
+  password_expires_in: number | null; // days, negative if already expired, null if not readable
+  home_state: string | null; // homed only, "absent" if storage is missing
   // lightdm only
   background?: string | null;
   logged_in?: boolean;
@@ -72,8 +114,16 @@ type GreeterRequestMethod = {
     | "languages"
     | "layout"
     | "layouts"
+    | "lock_keys"
     | "sessions"
     | "users"
+    | "hide_users_hint"
+    | "first_boot"
+    | "create_first_user"
+    | "set_language"
+    | "set_greeter_language"
+    | "existing_sessions"
+    | "activate_existing_session"
     // greetd + lightdm
     | "authentication_user"
     | "in_authentication"
@@ -88,7 +138,6 @@ type GreeterRequestMethod = {
     | "autologin_user"
     | "default_session"
     | "has_guest_account"
-    | "hide_users_hint"
     | "hostname"
     | "lock_hint"
     | "remote_sessions"
@@ -97,12 +146,11 @@ type GreeterRequestMethod = {
     | "show_manual_login_hint"
     | "show_remote_login_hint"
     | "shared_data_directory"
-    | "set_language"
     | "authenticate_as_guest"
     | "cancel_autologin";
   greeter_comm: "broadcast";
   greeter_config: "branding" | "greeter";
-  theme_utils: "dirlist";
+  theme_utils: "dirlist" | "translate";
 };
 
 type GreeterRequestReturnType =
@@ -115,6 +163,7 @@ type GreeterRequestReturnType =
   | Language[]
   | Layout
   | Layout[]
+  | LoginSession[]
   | Session[]
   | User[]
   | GreeterConfigBranding
@@ -139,6 +188,9 @@ export type {
   GreeterRequestTarget,
   Language,
   Layout,
+  LockKeys,
+  LoginRecord,
+  LoginSession,
   Session,
   User,
 };
@@ -156,6 +208,7 @@ export class GreeterConfig {
 
 export class ThemeUtils {
   dirlist(path: string, only_image?: boolean): Promise<string[]>;
+  translate(msgid: string): string; // msgid if the theme has no translation
 }
 
 export class Signal {
@@ -168,6 +221,13 @@ export class Greeter {
   show_prompt: Signal;
   show_message: Signal;
   authentication_complete: Signal;
+  screensaver_start: Signal;
+  screensaver_end: Signal;
+  users_changed: Signal;
+  sessions_changed: Signal;
+  language_changed: Signal;
+  layout_changed: Signal;
+  lock_keys_changed: Signal;
   get can_hibernate(): boolean;
   get can_restart(): boolean;
   get can_shutdown(): boolean;
@@ -178,16 +238,29 @@ export class Greeter {
   suspend(): boolean;
   get language(): Language | null;
   get languages(): Language[];
-  get layout(): Layout;
+  get layout(): Layout | null;
   set layout(layout: string);
   get layouts(): Layout[];
+  get lock_keys(): LockKeys;
   get sessions(): Session[];
   get users(): User[];
+  get hide_users_hint(): boolean;
+  get first_boot(): boolean;
+  get existing_sessions(): LoginSession[];
+  activate_existing_session(username: string): boolean; // after authenticate
+  create_first_user(
+    username: string,
+    display_name: string,
+    password: string,
+  ): boolean;
+  set_language(language: string): boolean;
+  set_greeter_language(language: string): boolean;
   get authentication_user(): string | null;
   get in_authentication(): boolean;
   get is_authenticated(): boolean;
   authenticate(username: string | null): boolean;
   cancel_authentication(): boolean;
   respond(password?: string | null): boolean;
-  start_session(session: string): boolean;
+  // uwsm: true to run a uwsm-managed unit, false to run directly, greetd only
+  start_session(session: string, uwsm?: boolean): boolean;
 }
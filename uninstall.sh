@@ -19,4 +19,5 @@ if [ -d /usr/lib/$_pkgname ]; then
   sudo rm "/usr/lib/sysusers.d/$_pkgname.conf"
   sudo rm "/usr/lib/tmpfiles.d/$_pkgname.conf"
   sudo rm -r /usr/share/webkit-greeter/themes/litarvan
+  sudo rm -f /usr/share/locale/*/LC_MESSAGES/$_pkgname.mo
 fi